@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+/// Returns the delay before the given attempt, starting at 1: the initial backoff, doubled after
+/// each attempt, up to the max backoff.
+pub(crate) fn exponential_backoff(
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    attempt: u32,
+) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+
+    initial_backoff.saturating_mul(factor).min(max_backoff)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::exponential_backoff;
+
+    #[test]
+    fn test_exponential_backoff() {
+        let backoff =
+            |attempt| exponential_backoff(Duration::from_secs(1), Duration::from_secs(10), attempt);
+
+        assert_eq!(backoff(0), Duration::from_secs(1));
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(8));
+        assert_eq!(backoff(5), Duration::from_secs(10));
+        assert_eq!(backoff(u32::MAX), Duration::from_secs(10));
+    }
+}
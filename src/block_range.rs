@@ -0,0 +1,190 @@
+use std::{ops::RangeInclusive, time::Duration};
+
+use alloy::{rpc::json_rpc::RpcError, transports::TransportErrorKind};
+
+use crate::backoff::exponential_backoff;
+
+/// How [`ReactorClient::backfill_fill_events`] splits its block range.
+///
+/// [`ReactorClient::backfill_fill_events`]: crate::ReactorClient::backfill_fill_events
+#[derive(Debug, Clone, Copy)]
+pub struct BackfillConfig {
+    /// The number of blocks queried at once, unless the provider rejects it.
+    pub chunk_size: u64,
+    /// The number of chunks queried concurrently.
+    pub concurrency: usize,
+    /// The number of times a rate limited query is retried before giving up.
+    pub max_retries: u32,
+    /// The delay before retrying a rate limited query, doubled after each retry.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl BackfillConfig {
+    /// Returns the delay before the given retry, starting at 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        exponential_backoff(self.initial_backoff, self.max_backoff, retry)
+    }
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: 2000,
+            concurrency: 4,
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Splits `from..=to` into consecutive ranges of at most `size` blocks.
+pub(crate) fn chunk_range(from: u64, to: u64, size: u64) -> Vec<RangeInclusive<u64>> {
+    let size = size.max(1);
+    let mut chunks = vec![];
+    let mut start = from;
+
+    while start <= to {
+        let end = start.saturating_add(size - 1).min(to);
+        chunks.push(start..=end);
+
+        if end == to {
+            break;
+        }
+
+        start = end + 1;
+    }
+
+    chunks
+}
+
+/// Checks whether the provider rejected an `eth_getLogs` query because its block range was too
+/// wide or it matched too many logs. Providers don't agree on an error for this, and some share
+/// its code with rate limits (Infura's `-32005`), so this only relies on the wording of their
+/// messages.
+pub(crate) fn is_range_too_large<E>(err: &RpcError<E>) -> bool {
+    let Some(payload) = err.as_error_resp() else {
+        return false;
+    };
+
+    let message = payload.message.to_lowercase();
+
+    [
+        // Infura
+        "query returned more than",
+        // Alchemy
+        "log response size exceeded",
+        // QuickNode
+        "eth_getlogs is limited to",
+        // Ankr, Erigon, BlastAPI...
+        "block range",
+        "max results",
+    ]
+    .iter()
+    .any(|pattern| message.contains(pattern))
+}
+
+/// Checks whether the provider rejected a query because too many were sent, either with a `429`
+/// HTTP status or with one of the JSON-RPC errors providers use for it, as recognized by alloy
+/// along with a few transient load balancer errors.
+pub(crate) fn is_rate_limited(err: &RpcError<TransportErrorKind>) -> bool {
+    match err {
+        RpcError::Transport(kind) => kind.is_retry_err(),
+        RpcError::ErrorResp(payload) => !is_range_too_large(err) && payload.is_retry_err(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::rpc::json_rpc::{ErrorPayload, RpcError};
+
+    use std::time::Duration;
+
+    use alloy::transports::TransportErrorKind;
+
+    use super::{chunk_range, is_range_too_large, is_rate_limited, BackfillConfig};
+
+    #[test]
+    fn test_chunk_range() {
+        assert_eq!(
+            chunk_range(100, 350, 100),
+            vec![100..=199, 200..=299, 300..=350]
+        );
+        assert_eq!(chunk_range(100, 100, 100), vec![100..=100]);
+        assert_eq!(chunk_range(101, 100, 100), vec![]);
+        assert_eq!(
+            chunk_range(u64::MAX - 1, u64::MAX, 1),
+            vec![u64::MAX - 1..=u64::MAX - 1, u64::MAX..=u64::MAX]
+        );
+    }
+
+    #[test]
+    fn test_backoff() {
+        let config = BackfillConfig {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            ..Default::default()
+        };
+
+        assert_eq!(config.backoff(1), Duration::from_secs(1));
+        assert_eq!(config.backoff(3), Duration::from_secs(4));
+        assert_eq!(config.backoff(100), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_is_range_too_large() {
+        let error = |payload: &str| {
+            RpcError::<TransportErrorKind>::ErrorResp(
+                serde_json::from_str::<ErrorPayload>(payload).unwrap(),
+            )
+        };
+
+        assert!(is_range_too_large(&error(
+            r#"{"code":-32005,"message":"query returned more than 10000 results"}"#
+        )));
+        assert!(is_range_too_large(&error(
+            r#"{"code":-32600,"message":"eth_getLogs is limited to a 10,000 block range"}"#
+        )));
+        assert!(is_range_too_large(&error(
+            r#"{"code":-32602,"message":"Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range and no limit on the response size, or you can request any block range with a cap of 10K logs in the response."}"#
+        )));
+        assert!(!is_range_too_large(&error(
+            r#"{"code":-32000,"message":"header not found"}"#
+        )));
+        // Infura shares its code with rate limits
+        assert!(!is_range_too_large(&error(
+            r#"{"code":-32005,"message":"daily request count exceeded, request rate limited"}"#
+        )));
+        assert!(!is_range_too_large(&error(
+            r#"{"code":-32005,"message":"project ID request rate exceeded"}"#
+        )));
+    }
+
+    #[test]
+    fn test_is_rate_limited() {
+        let error = |payload: &str| {
+            RpcError::<TransportErrorKind>::ErrorResp(
+                serde_json::from_str::<ErrorPayload>(payload).unwrap(),
+            )
+        };
+
+        assert!(is_rate_limited(&error(
+            r#"{"code":-32005,"message":"project ID request rate exceeded"}"#
+        )));
+        assert!(is_rate_limited(&error(
+            r#"{"code":429,"message":"Your app has exceeded its compute units per second capacity"}"#
+        )));
+        assert!(is_rate_limited(&TransportErrorKind::http_error(
+            429,
+            "Too Many Requests".into()
+        )));
+        assert!(!is_rate_limited(&error(
+            r#"{"code":-32005,"message":"query returned more than 10000 results"}"#
+        )));
+        assert!(!is_rate_limited(&error(
+            r#"{"code":-32602,"message":"invalid params"}"#
+        )));
+    }
+}
@@ -0,0 +1,190 @@
+use futures::{
+    future::{self, Either},
+    stream, Stream, StreamExt,
+};
+
+use crate::types::{FillEvent, FillNotification};
+
+/// Holds fills back until enough blocks are built on top of them, so that most reorgs are
+/// absorbed before the fills are streamed.
+#[derive(Debug)]
+pub(crate) struct ConfirmationBuffer {
+    confirmations: u64,
+    head: u64,
+    pending: Vec<FillEvent>,
+}
+
+impl ConfirmationBuffer {
+    pub(crate) fn new(confirmations: u64) -> Self {
+        Self {
+            confirmations,
+            head: 0,
+            pending: vec![],
+        }
+    }
+
+    /// Buffers a confirmed fill, or drops a reverted one which is still pending. Reverted fills
+    /// which were already released are passed through, as they must be undone downstream.
+    pub(crate) fn push(&mut self, notification: FillNotification) -> Vec<FillNotification> {
+        match notification {
+            FillNotification::Confirmed(fill) => {
+                let block_number = fill.block_number;
+
+                self.pending.push(fill);
+                self.advance(block_number)
+            }
+            FillNotification::Reverted(fill) => {
+                let pending = self.pending.iter().position(|pending| {
+                    pending.order_hash == fill.order_hash && pending.tx == fill.tx
+                });
+
+                match pending {
+                    Some(index) => {
+                        self.pending.remove(index);
+                        vec![]
+                    }
+                    None => vec![FillNotification::Reverted(fill)],
+                }
+            }
+        }
+    }
+
+    /// Moves the head of the chain, releasing the fills with enough confirmations in block
+    /// order. A fill of block `n` is released once the head reaches `n + confirmations`.
+    pub(crate) fn advance(&mut self, head: u64) -> Vec<FillNotification> {
+        self.head = self.head.max(head);
+
+        let (mut released, pending) = self.pending.drain(..).partition::<Vec<_>, _>(|fill| {
+            fill.block_number.saturating_add(self.confirmations) <= self.head
+        });
+
+        self.pending = pending;
+        released.sort();
+
+        released
+            .into_iter()
+            .map(FillNotification::Confirmed)
+            .collect()
+    }
+}
+
+/// Delays the confirmed fills of a live stream by the given number of blocks, using the heads
+/// of the chain to know when they're confirmed enough.
+pub(crate) fn confirm_fills<N, H, E>(
+    notifications: N,
+    heads: H,
+    confirmations: u64,
+) -> impl Stream<Item = Result<FillNotification, E>>
+where
+    N: Stream<Item = Result<FillNotification, E>>,
+    H: Stream<Item = u64>,
+{
+    stream::select(notifications.map(Either::Left), heads.map(Either::Right))
+        .scan(ConfirmationBuffer::new(confirmations), |buffer, item| {
+            let released = match item {
+                Either::Left(Ok(notification)) => {
+                    buffer.push(notification).into_iter().map(Ok).collect()
+                }
+                Either::Left(Err(err)) => vec![Err(err)],
+                Either::Right(head) => buffer.advance(head).into_iter().map(Ok).collect(),
+            };
+
+            future::ready(Some(stream::iter(released)))
+        })
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::B256;
+    use futures::{stream, StreamExt};
+
+    use crate::types::{FillEvent, FillNotification};
+
+    use super::{confirm_fills, ConfirmationBuffer};
+
+    fn fill(order_hash: u8, block_number: u64) -> FillEvent {
+        FillEvent {
+            order_hash: B256::repeat_byte(order_hash),
+            tx: B256::repeat_byte(order_hash),
+            block_number,
+            block_hash: B256::with_last_byte(block_number as u8),
+            ..Default::default()
+        }
+    }
+
+    fn confirmed(notifications: &[FillNotification]) -> Vec<(B256, bool)> {
+        notifications
+            .iter()
+            .map(|notification| {
+                (
+                    notification.fill().order_hash,
+                    matches!(notification, FillNotification::Confirmed(_)),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_release_after_confirmations() {
+        let mut buffer = ConfirmationBuffer::new(2);
+
+        assert!(buffer
+            .push(FillNotification::Confirmed(fill(1, 100)))
+            .is_empty());
+        assert!(buffer
+            .push(FillNotification::Confirmed(fill(2, 101)))
+            .is_empty());
+        assert_eq!(
+            confirmed(&buffer.advance(102)),
+            vec![(B256::repeat_byte(1), true)]
+        );
+        assert_eq!(
+            confirmed(&buffer.advance(103)),
+            vec![(B256::repeat_byte(2), true)]
+        );
+    }
+
+    #[test]
+    fn test_drop_pending_reverted_fill() {
+        let mut buffer = ConfirmationBuffer::new(2);
+
+        buffer.push(FillNotification::Confirmed(fill(1, 100)));
+
+        assert!(buffer
+            .push(FillNotification::Reverted(fill(1, 100)))
+            .is_empty());
+        assert!(buffer.advance(110).is_empty());
+    }
+
+    #[test]
+    fn test_pass_released_reverted_fill() {
+        let mut buffer = ConfirmationBuffer::new(0);
+
+        assert_eq!(
+            confirmed(&buffer.push(FillNotification::Confirmed(fill(1, 100)))),
+            vec![(B256::repeat_byte(1), true)]
+        );
+        assert_eq!(
+            confirmed(&buffer.push(FillNotification::Reverted(fill(1, 100)))),
+            vec![(B256::repeat_byte(1), false)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirm_fills() {
+        let notifications = stream::iter([
+            Ok::<_, ()>(FillNotification::Confirmed(fill(1, 100))),
+            Ok(FillNotification::Confirmed(fill(2, 100))),
+            Ok(FillNotification::Reverted(fill(2, 100))),
+        ]);
+        let heads = stream::iter([101, 102, 103]);
+
+        let released = confirm_fills(notifications, heads, 3)
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(confirmed(&released), vec![(B256::repeat_byte(1), true)]);
+    }
+}
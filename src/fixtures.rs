@@ -0,0 +1,10 @@
+//! Orders shared by the unit tests.
+
+/// A Dutch V1 order swapping 100 1INCH for WETH on mainnet, as returned by the orders API.
+pub(crate) const DUTCH_V1_ORDER: &str = "0x000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000001200000000000000000000000000000000000000000000000000000000064b51b750000000000000000000000000000000000000000000000000000000064b51b75000000000000000000000000b507d4ef5ed7a01e37cb578f497329cdb3c273a50000000000000000000000000000000000000000000000000000000000002710000000000000000000000000111111111117dc0aa78b770fa6a738034120c3020000000000000000000000000000000000000000000000056bc75e2d631000000000000000000000000000000000000000000000000000056bc75e2d631000000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000e80bf394d190851e215d5f67b67f8f5a52783f1e000000000000000000000000b8bff65b2eeb60d6b37312ca0740a742d5e7f95500000000000000000000000000000000000000000000000000000189635c5eac0000000000000000000000000000000000000000000000000000000064b51b75000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000c000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc20000000000000000000000000000000000000000000000000039a65e493e91140000000000000000000000000000000000000000000000000039a65e493e9114000000000000000000000000b8bff65b2eeb60d6b37312ca0740a742d5e7f955";
+
+/// The swapper's Permit2 signature of [`DUTCH_V1_ORDER`].
+pub(crate) const DUTCH_V1_SIGNATURE: &str = "0x3dd85ac7743719d3d5275e21062ef8e2d98acbf8b5ceb1e0436c3dc70cb16d812de7df3c9e15dc56295d563b2c36798155dae63abb074df6aa3d6e1cdc6257c91b";
+
+/// The deadline of [`DUTCH_V1_ORDER`].
+pub(crate) const DUTCH_V1_DEADLINE: u64 = 1689590645;
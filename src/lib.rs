@@ -1,9 +1,33 @@
+mod backoff;
+mod block_range;
+mod confirmations;
+#[cfg(test)]
+mod fixtures;
+mod open_orders;
 mod order_stream;
+mod order_tracker;
 mod orders_service;
+mod orders_stream_config;
+mod reactor_calls;
 mod reactor_client;
 mod reactor_config;
+mod reactor_contract;
+mod reactor_error;
+mod resubscribe;
 pub mod types;
 
-pub use order_stream::{orders_stream, StreamError};
+pub use block_range::BackfillConfig;
+pub use open_orders::{open_orders_stream, OpenOrderEvent, OpenOrdersConfig};
+pub use order_stream::{
+    orders_stream, orders_stream_with_config, orders_stream_with_service, StreamError,
+};
+pub use order_tracker::{OrderTracker, StatusChanged};
 pub use orders_service::{OrdersError, OrdersService};
-pub use reactor_client::ReactorClient;
+pub use orders_stream_config::{OrdersStreamConfig, RetryConfig};
+pub use reactor_calls::{ExecuteBuilder, ExecuteCall, ExecuteError, SignedOrder};
+pub use reactor_client::{
+    FillEventStream, FillSimulation, FillStreamError, ReactorClient, ReactorClientError,
+};
+pub use reactor_config::{ReactorConfig, ReactorConfigError, ReactorDeployment, PERMIT2_ADDRESS};
+pub use reactor_error::ReactorError;
+pub use resubscribe::ResubscribeConfig;
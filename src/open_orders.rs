@@ -0,0 +1,414 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    mem,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::B256;
+use futures::{stream::iter, Stream, StreamExt};
+
+use crate::{
+    order_stream::{poll_orders, Paging, PollConfig, StreamError},
+    orders_service::OrdersService,
+    orders_stream_config::OrdersStreamConfig,
+    types::{Order, OrderStatus, OrdersRequest},
+};
+
+/// A change in the set of open orders.
+#[derive(Debug, Clone)]
+pub enum OpenOrderEvent {
+    /// An order seen open for the first time.
+    New(Order),
+    /// A previously open order the API doesn't return anymore, e.g. because it was filled or
+    /// cancelled.
+    Removed(Order),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OpenOrdersConfig {
+    pub stream: OrdersStreamConfig,
+    /// The maximum number of orders remembered. Past it, the orders with the earliest deadline
+    /// are evicted first: only their hash is kept, until their deadline passes, so that they're
+    /// not streamed again.
+    pub capacity: usize,
+    /// The maximum number of evicted order hashes kept. Past it, the ones with the earliest
+    /// deadline are dropped, and those orders would be streamed again if still open.
+    pub evicted_capacity: usize,
+    /// How long orders whose deadline can't be decoded are remembered for, from the first time
+    /// they're seen.
+    pub undecodable_ttl: Duration,
+    /// Whether to stream [`OpenOrderEvent::Removed`] events. Removals are detected by walking
+    /// every page of the open orders at each poll, rather than resuming after the last one.
+    pub removals: bool,
+}
+
+impl Default for OpenOrdersConfig {
+    fn default() -> Self {
+        Self {
+            stream: OrdersStreamConfig::default(),
+            capacity: 10_000,
+            evicted_capacity: 100_000,
+            undecodable_ttl: Duration::from_secs(3600),
+            removals: false,
+        }
+    }
+}
+
+/// Polls the open orders matching the request, and only streams the orders which weren't seen
+/// before. The order status of the request is overridden with [`OrderStatus::Open`].
+pub fn open_orders_stream(
+    url: String,
+    mut request: OrdersRequest,
+    config: OpenOrdersConfig,
+) -> impl Stream<Item = Result<OpenOrderEvent, StreamError>> {
+    request.order_status = Some(OrderStatus::Open);
+
+    let orders_service = config.stream.layer(OrdersService::new(url));
+    let mut open_orders = OpenOrders::new(&config);
+
+    // removals are detected from complete result sets, which resuming after the last order
+    // would prevent
+    let poll_config = PollConfig {
+        poll_interval: config.stream.poll_interval,
+        max_consecutive_failures: config.stream.max_consecutive_failures,
+        paging: if config.removals {
+            Paging::Cycle
+        } else {
+            Paging::Resume
+        },
+    };
+
+    // the pages of the current cycle, diffed at once after the last one
+    let mut cycle = vec![];
+
+    poll_orders(orders_service, request, poll_config).flat_map(move |payload| {
+        let events = match payload {
+            Ok(payload) if config.removals => {
+                cycle.extend(payload.orders);
+
+                if payload.cursor.is_some() {
+                    vec![]
+                } else {
+                    open_orders
+                        .update(mem::take(&mut cycle), true, unix_timestamp())
+                        .into_iter()
+                        .map(Ok)
+                        .collect()
+                }
+            }
+            Ok(payload) => open_orders
+                .update(payload.orders, false, unix_timestamp())
+                .into_iter()
+                .map(Ok)
+                .collect(),
+            Err(err) => vec![Err(err)],
+        };
+
+        iter(events)
+    })
+}
+
+/// The open orders seen so far, until their deadline passes. Both the orders and the evicted
+/// hashes are bounded by the config.
+pub(crate) struct OpenOrders {
+    /// The remembered orders, by deadline so that the earliest ones are evicted first.
+    orders: BTreeMap<(u64, B256), Order>,
+    /// The orders evicted past the capacity, remembered until their deadline passes.
+    evicted: BTreeSet<(u64, B256)>,
+    /// The deadline of every order, remembered or evicted.
+    deadlines: HashMap<B256, u64>,
+    capacity: usize,
+    evicted_capacity: usize,
+    undecodable_ttl: u64,
+    removals: bool,
+}
+
+impl OpenOrders {
+    pub(crate) fn new(config: &OpenOrdersConfig) -> Self {
+        Self {
+            orders: BTreeMap::new(),
+            evicted: BTreeSet::new(),
+            deadlines: HashMap::new(),
+            capacity: config.capacity.max(1),
+            evicted_capacity: config.evicted_capacity,
+            undecodable_ttl: config.undecodable_ttl.as_secs(),
+            removals: config.removals,
+        }
+    }
+
+    /// Updates the open orders with the result of a poll, `complete` if it holds every open
+    /// order, and returns the changes.
+    pub(crate) fn update(
+        &mut self,
+        orders: Vec<Order>,
+        complete: bool,
+        now: u64,
+    ) -> Vec<OpenOrderEvent> {
+        let mut events = vec![];
+
+        if self.removals && complete {
+            let returned = orders
+                .iter()
+                .map(|order| order.order_hash)
+                .collect::<HashSet<_>>();
+
+            let removed = self
+                .deadlines
+                .iter()
+                .filter(|(order_hash, _)| !returned.contains(*order_hash))
+                .map(|(order_hash, deadline)| (*deadline, *order_hash))
+                .collect::<Vec<_>>();
+
+            for key in removed {
+                self.deadlines.remove(&key.1);
+                self.evicted.remove(&key);
+
+                if let Some(order) = self.orders.remove(&key) {
+                    events.push(OpenOrderEvent::Removed(order));
+                }
+            }
+        }
+
+        // expired orders can't be filled, there is no need to remember them
+        self.expire(now);
+
+        for order in orders {
+            if self.deadlines.contains_key(&order.order_hash) {
+                continue;
+            }
+
+            let deadline = order
+                .deadline()
+                .map(|deadline| deadline.saturating_to::<u64>())
+                .unwrap_or(now.saturating_add(self.undecodable_ttl));
+
+            if deadline < now {
+                continue;
+            }
+
+            if self.orders.len() >= self.capacity {
+                if let Some((key, _)) = self.orders.pop_first() {
+                    self.evict(key);
+                }
+            }
+
+            self.deadlines.insert(order.order_hash, deadline);
+            self.orders
+                .insert((deadline, order.order_hash), order.clone());
+            events.push(OpenOrderEvent::New(order));
+        }
+
+        events
+    }
+
+    /// Keeps the hash of an evicted order, dropping the earliest evicted ones past the capacity.
+    fn evict(&mut self, key: (u64, B256)) {
+        self.evicted.insert(key);
+
+        while self.evicted.len() > self.evicted_capacity {
+            if let Some((_, order_hash)) = self.evicted.pop_first() {
+                self.deadlines.remove(&order_hash);
+            }
+        }
+    }
+
+    /// Forgets the orders whose deadline passed, remembered or evicted.
+    fn expire(&mut self, now: u64) {
+        let live = self.orders.split_off(&(now, B256::ZERO));
+        let expired = mem::replace(&mut self.orders, live);
+
+        let live = self.evicted.split_off(&(now, B256::ZERO));
+        let evicted = mem::replace(&mut self.evicted, live);
+
+        for (_, order_hash) in expired.into_keys().chain(evicted) {
+            self.deadlines.remove(&order_hash);
+        }
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use alloy::primitives::B256;
+
+    use crate::{
+        fixtures::{DUTCH_V1_DEADLINE as DEADLINE, DUTCH_V1_ORDER},
+        types::{Order, OrderType},
+    };
+
+    use super::{OpenOrderEvent, OpenOrders, OpenOrdersConfig};
+
+    const TTL: u64 = 3600;
+
+    fn open_orders(capacity: usize, removals: bool) -> OpenOrders {
+        OpenOrders::new(&OpenOrdersConfig {
+            capacity,
+            undecodable_ttl: Duration::from_secs(TTL),
+            removals,
+            ..Default::default()
+        })
+    }
+
+    /// An order which can't be decoded, so it's remembered for [`TTL`].
+    fn order(order_hash: u8) -> Order {
+        Order {
+            order_hash: B256::repeat_byte(order_hash),
+            order_type: OrderType::Limit,
+            ..Default::default()
+        }
+    }
+
+    fn new_orders(events: &[OpenOrderEvent]) -> Vec<u8> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                OpenOrderEvent::New(order) => Some(order.order_hash[0]),
+                OpenOrderEvent::Removed(_) => None,
+            })
+            .collect()
+    }
+
+    fn remembered(open_orders: &OpenOrders) -> Vec<u8> {
+        open_orders
+            .orders
+            .values()
+            .map(|order| order.order_hash[0])
+            .collect()
+    }
+
+    fn removed_orders(events: &[OpenOrderEvent]) -> Vec<u8> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                OpenOrderEvent::Removed(order) => Some(order.order_hash[0]),
+                OpenOrderEvent::New(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_only_new_orders() {
+        let mut open_orders = open_orders(10, false);
+
+        let events = open_orders.update(vec![order(1), order(2)], true, 0);
+        assert_eq!(new_orders(&events), vec![1, 2]);
+
+        let events = open_orders.update(vec![order(1), order(2), order(3)], true, 0);
+        assert_eq!(new_orders(&events), vec![3]);
+
+        // without removals, orders dropping out are still remembered
+        let events = open_orders.update(vec![order(3)], true, 0);
+        assert!(events.is_empty());
+
+        let events = open_orders.update(vec![order(1)], true, 0);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_removals() {
+        let mut open_orders = open_orders(10, true);
+
+        open_orders.update(vec![order(1), order(2)], true, 0);
+
+        let events = open_orders.update(vec![order(2), order(3)], true, 0);
+        assert_eq!(removed_orders(&events), vec![1]);
+        assert_eq!(new_orders(&events), vec![3]);
+
+        // an incomplete result set says nothing about the missing orders
+        let events = open_orders.update(vec![order(3)], false, 0);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_expiry() {
+        let mut open_orders = open_orders(10, false);
+        let expiring = Order {
+            order_type: OrderType::Dutch,
+            encoded_order: DUTCH_V1_ORDER.parse().unwrap(),
+            ..order(1)
+        };
+
+        let events = open_orders.update(vec![expiring.clone()], true, DEADLINE);
+        assert_eq!(new_orders(&events), vec![1]);
+
+        // the order is forgotten once expired, and not streamed again
+        let events = open_orders.update(vec![expiring], true, DEADLINE + 1);
+        assert!(events.is_empty());
+        assert!(open_orders.orders.is_empty());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut open_orders = open_orders(2, false);
+        let expiring = Order {
+            order_type: OrderType::Dutch,
+            encoded_order: DUTCH_V1_ORDER.parse().unwrap(),
+            ..order(9)
+        };
+
+        let events = open_orders.update(vec![expiring.clone(), order(1), order(2)], true, 0);
+        assert_eq!(new_orders(&events), vec![9, 1, 2]);
+
+        // the order with the earliest deadline is evicted first, and orders with the same
+        // deadline by hash
+        assert_eq!(remembered(&open_orders), vec![2, 9]);
+
+        // evicted orders aren't streamed again
+        let events = open_orders.update(vec![order(1), expiring, order(2), order(3)], true, 0);
+        assert_eq!(new_orders(&events), vec![3]);
+        assert_eq!(remembered(&open_orders), vec![3, 9]);
+
+        // evicted orders are forgotten once expired
+        open_orders.update(vec![], true, TTL + 1);
+        assert!(open_orders.evicted.is_empty());
+        assert_eq!(open_orders.deadlines.len(), 1);
+        assert_eq!(remembered(&open_orders), vec![9]);
+    }
+
+    #[test]
+    fn test_undecodable_orders_are_bounded() {
+        let mut open_orders = OpenOrders::new(&OpenOrdersConfig {
+            capacity: 2,
+            evicted_capacity: 3,
+            ..Default::default()
+        });
+
+        for batch in 0..10 {
+            let orders = (0..10).map(|i| order(batch * 10 + i)).collect();
+            open_orders.update(orders, false, 0);
+
+            assert!(open_orders.orders.len() <= 2);
+            assert!(open_orders.evicted.len() <= 3);
+            assert!(open_orders.deadlines.len() <= 5);
+        }
+
+        // the undecodable orders expire like any other
+        open_orders.update(vec![], false, 3600 + 1);
+        assert!(open_orders.orders.is_empty());
+        assert!(open_orders.evicted.is_empty());
+        assert!(open_orders.deadlines.is_empty());
+    }
+
+    #[test]
+    fn test_removals_of_evicted_orders() {
+        let mut open_orders = open_orders(1, true);
+
+        open_orders.update(vec![order(1), order(2)], true, 0);
+
+        // order 1 was evicted, so its removal isn't streamed
+        let events = open_orders.update(vec![order(2)], true, 0);
+        assert!(events.is_empty());
+        assert!(open_orders.evicted.is_empty());
+
+        let events = open_orders.update(vec![], true, 0);
+        assert_eq!(removed_orders(&events), vec![2]);
+    }
+}
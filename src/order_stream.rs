@@ -7,60 +7,191 @@ use futures::{
 };
 
 use thiserror::Error;
-use tower::{Service, ServiceBuilder, ServiceExt};
+use tower::{Service, ServiceExt};
 
 use crate::{
     orders_service::OrdersService,
-    types::{Order, OrderStatus, OrdersRequest},
+    orders_stream_config::OrdersStreamConfig,
+    types::{CursorError, Order, OrderPayload, OrdersRequest},
     OrdersError,
 };
 
+/// Streams the orders matching the request from the orders API, with the default
+/// [`OrdersStreamConfig`].
 pub fn orders_stream(
     url: String,
     request: OrdersRequest,
 ) -> impl Stream<Item = Result<Order, StreamError>> {
-    let orders_service = ServiceBuilder::new()
-        .rate_limit(1, Duration::from_secs(3))
-        .service(OrdersService::new(url));
-
-    stream::unfold(
-        (orders_service, request),
-        |(mut orders_service, mut current_request)| async move {
-            match orders_service.ready().await {
-                Ok(service) => match service.call(current_request.clone()).await {
-                    Ok(payload) => {
-                        if let Some(order_status) = &current_request.order_status {
-                            if matches!(order_status, OrderStatus::Filled) {
-                                let next_request_cursor = payload
-                                    .cursor
-                                    .or(current_request.cursor.clone())
-                                    .or(current_request.cursor_from_order(payload.orders.last()));
-
-                                current_request = current_request.with_cursor(next_request_cursor);
+    orders_stream_with_config(url, request, OrdersStreamConfig::default())
+}
+
+pub fn orders_stream_with_config(
+    url: String,
+    request: OrdersRequest,
+    config: OrdersStreamConfig,
+) -> impl Stream<Item = Result<Order, StreamError>> {
+    let orders_service = config.layer(OrdersService::new(url));
+
+    orders_stream_with_service(
+        orders_service,
+        request,
+        config.poll_interval,
+        config.max_consecutive_failures,
+    )
+}
+
+/// Streams the orders matching the request through the given service, which is expected to
+/// handle retries and rate limiting itself, e.g. with [`OrdersStreamConfig::layer`]. The stream
+/// ends after `max_consecutive_failures` failed requests in a row, if set, or with an
+/// [`OrdersError::Cursor`] error if it can't resume after the last order of a page.
+pub fn orders_stream_with_service<S>(
+    orders_service: S,
+    request: OrdersRequest,
+    poll_interval: Duration,
+    max_consecutive_failures: Option<u32>,
+) -> impl Stream<Item = Result<Order, StreamError>>
+where
+    S: Service<OrdersRequest, Response = OrderPayload, Error = OrdersError>,
+{
+    let config = PollConfig {
+        poll_interval,
+        max_consecutive_failures,
+        paging: Paging::Resume,
+    };
+
+    poll_orders(orders_service, request, config).flat_map(|payload| match payload {
+        Ok(payload) => Either::Right(iter(payload.orders).map(Ok)),
+        Err(err) => Either::Left(once(async { Err(err) })),
+    })
+}
+
+/// How [`poll_orders`] paces its requests and moves through the pages of the API.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PollConfig {
+    pub(crate) poll_interval: Duration,
+    pub(crate) max_consecutive_failures: Option<u32>,
+    pub(crate) paging: Paging,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Paging {
+    /// Each request resumes after the last order received, whatever the order status, and the
+    /// poll interval is awaited once there is no next page.
+    Resume,
+    /// Each cycle walks every page from the cursor of the initial request, and the poll interval
+    /// is awaited between cycles. The last page of a cycle is the one without a cursor.
+    Cycle,
+}
+
+/// Polls the orders API, streaming the response of each request.
+pub(crate) fn poll_orders<S>(
+    orders_service: S,
+    request: OrdersRequest,
+    config: PollConfig,
+) -> impl Stream<Item = Result<OrderPayload, StreamError>>
+where
+    S: Service<OrdersRequest, Response = OrderPayload, Error = OrdersError>,
+{
+    let state = PollState {
+        orders_service,
+        initial_cursor: request.cursor.clone(),
+        current_request: request,
+        consecutive_failures: 0,
+        wait: false,
+        cursor_error: None,
+        ended: false,
+    };
+
+    stream::unfold(state, move |mut state| async move {
+        if state.ended {
+            return None;
+        }
+
+        if let Some(err) = state.cursor_error.take() {
+            state.ended = true;
+
+            return Some((Err(err), state));
+        }
+
+        if let Some(max_failures) = config.max_consecutive_failures {
+            if state.consecutive_failures >= max_failures {
+                return None;
+            }
+        }
+
+        if state.wait {
+            tokio::time::sleep(config.poll_interval).await;
+        }
+
+        let result = match state.orders_service.ready().await {
+            Ok(service) => service.call(state.current_request.clone()).await,
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(payload) => {
+                state.consecutive_failures = 0;
+                // the stream caught up once there is no next page
+                state.wait = payload.cursor.is_none();
+
+                let next_request_cursor = match config.paging {
+                    Paging::Resume if payload.cursor.is_some() => payload.cursor.clone(),
+                    Paging::Resume => {
+                        match next_cursor(&state.current_request, payload.orders.last()) {
+                            Ok(cursor) => cursor.or(state.current_request.cursor.clone()),
+                            Err(err) => {
+                                // the same page would be streamed again and again, the stream
+                                // ends with the error instead
+                                let err =
+                                    StreamError::new(err.into(), state.current_request.clone());
+                                state.cursor_error = Some(err);
+
+                                None
                             }
                         }
-
-                        Some((Ok(payload.orders), (orders_service, current_request)))
                     }
-                    Err(err) => Some((
-                        Err(StreamError::new(err, current_request.clone())),
-                        (orders_service, current_request),
-                    )),
-                },
-                Err(err) => Some((
-                    Err(StreamError::new(err, current_request.clone())),
-                    (orders_service, current_request),
-                )),
+                    Paging::Cycle => payload.cursor.clone().or(state.initial_cursor.clone()),
+                };
+
+                state.current_request = state.current_request.with_cursor(next_request_cursor);
+
+                Some((Ok(payload), state))
             }
-        },
-    )
-    .map(|x| {
-        x.map_or_else(
-            |err| Either::Left(once(async { Err(err) })),
-            |orders| Either::Right(iter(orders).map(Ok)),
-        )
+            Err(err) => {
+                state.consecutive_failures += 1;
+                state.wait = true;
+
+                let err = StreamError::new(err, state.current_request.clone());
+
+                Some((Err(err), state))
+            }
+        }
     })
-    .flatten()
+}
+
+/// Builds the cursor resuming after the last order of a page, when the API didn't return one.
+fn next_cursor(
+    request: &OrdersRequest,
+    last_order: Option<&Order>,
+) -> Result<Option<String>, CursorError> {
+    let Some(last_order) = last_order else {
+        return Ok(None);
+    };
+
+    Ok(Some(request.cursor_from_order(last_order)?.to_string()))
+}
+
+struct PollState<S> {
+    orders_service: S,
+    /// The cursor each cycle starts from, with [`Paging::Cycle`].
+    initial_cursor: Option<String>,
+    current_request: OrdersRequest,
+    consecutive_failures: u32,
+    /// Whether to wait for the poll interval before the next request.
+    wait: bool,
+    /// The error the stream ends with, once the page it follows is streamed.
+    cursor_error: Option<StreamError>,
+    ended: bool,
 }
 
 #[derive(Error, Debug)]
@@ -83,3 +214,122 @@ impl Display for StreamError {
         write!(f, "{}: {:?}", self.source, self.current_request)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use alloy::primitives::Address;
+    use futures::StreamExt;
+    use tower::service_fn;
+
+    use crate::{
+        types::{CursorError, Order, OrderPayload, OrderResponseError, OrdersRequest},
+        OrdersError,
+    };
+
+    use super::{orders_stream_with_service, poll_orders, Paging, PollConfig};
+
+    #[tokio::test]
+    async fn test_ends_after_max_consecutive_failures() {
+        let service = service_fn(|_: OrdersRequest| async {
+            let err =
+                serde_json::from_str::<OrderResponseError>(r#"{"errorCode":"INTERNAL_ERROR"}"#)
+                    .unwrap();
+
+            Err::<OrderPayload, _>(OrdersError::from(err))
+        });
+
+        let items = orders_stream_with_service(
+            service,
+            OrdersRequest::default(),
+            Duration::from_millis(1),
+            Some(3),
+        )
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(items.len(), 3);
+        assert!(items.iter().all(Result::is_err));
+    }
+
+    #[tokio::test]
+    async fn test_cycle_walks_every_page() {
+        let cursors = Arc::new(Mutex::new(vec![]));
+
+        let service = service_fn({
+            let cursors = cursors.clone();
+
+            move |request: OrdersRequest| {
+                cursors.lock().unwrap().push(request.cursor.clone());
+
+                // two pages, the second one without a next page
+                let cursor = match request.cursor.as_deref() {
+                    Some("start") => Some("page-2".to_string()),
+                    _ => None,
+                };
+
+                async move {
+                    Ok::<_, OrdersError>(OrderPayload {
+                        orders: vec![],
+                        cursor,
+                    })
+                }
+            }
+        });
+
+        let config = PollConfig {
+            poll_interval: Duration::from_millis(1),
+            max_consecutive_failures: None,
+            paging: Paging::Cycle,
+        };
+        let request = OrdersRequest::default().with_cursor(Some("start".into()));
+
+        let pages = poll_orders(service, request, config)
+            .take(4)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(pages.len(), 4);
+        assert_eq!(
+            *cursors.lock().unwrap(),
+            vec![
+                Some("start".to_string()),
+                Some("page-2".to_string()),
+                Some("start".to_string()),
+                Some("page-2".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ends_when_the_cursor_is_unsupported() {
+        // the API can't page through a chain id and a swapper at once
+        let request = OrdersRequest {
+            chain_id: Some(1),
+            swapper: Some(Address::repeat_byte(1)),
+            ..Default::default()
+        };
+
+        let service = service_fn(|_: OrdersRequest| async {
+            Ok::<_, OrdersError>(OrderPayload {
+                orders: vec![Order::default()],
+                cursor: None,
+            })
+        });
+
+        let items = orders_stream_with_service(service, request, Duration::from_millis(1), None)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(items.len(), 2);
+        assert!(items[0].is_ok());
+        assert!(matches!(
+            items[1].as_ref().unwrap_err().source,
+            OrdersError::Cursor(CursorError::UnsupportedFilters)
+        ));
+    }
+}
@@ -0,0 +1,385 @@
+use std::{collections::HashMap, time::Duration};
+
+use alloy::primitives::B256;
+use futures::{
+    future::{self, Either},
+    stream::{self, iter, once, FusedStream},
+    Stream, StreamExt,
+};
+use tokio::time::{sleep, Instant};
+use tower::{Service, ServiceExt};
+
+use crate::{
+    types::{FillEvent, Order, OrderPayload, OrderStatus, OrdersRequest, SettledAmount},
+    OrdersError,
+};
+
+/// An order moving from one status to another.
+#[derive(Debug, Clone)]
+pub struct StatusChanged {
+    pub order_hash: B256,
+    pub from: OrderStatus,
+    pub to: OrderStatus,
+    /// The transaction which filled the order, if it was filled.
+    pub tx_hash: Option<B256>,
+    pub settled_amounts: Option<Vec<SettledAmount>>,
+}
+
+/// Follows the status of a set of orders until they're settled, i.e. filled, cancelled, expired
+/// or errored. Orders are looked up by batches through the orders API, and can also be marked
+/// as filled from on-chain fill events.
+pub struct OrderTracker<S> {
+    orders_service: S,
+    chain_id: Option<u64>,
+    batch_size: usize,
+    orders: HashMap<B256, OrderStatus>,
+}
+
+impl<S> OrderTracker<S>
+where
+    S: Service<OrdersRequest, Response = OrderPayload, Error = OrdersError>,
+{
+    pub fn new(orders_service: S) -> Self {
+        Self {
+            orders_service,
+            chain_id: None,
+            batch_size: 25,
+            orders: HashMap::new(),
+        }
+    }
+
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Sets the number of orders looked up per request.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Starts tracking an order, assumed to be open.
+    pub fn track(&mut self, order_hash: B256) {
+        self.track_with_status(order_hash, OrderStatus::Open);
+    }
+
+    /// Starts tracking an order from its last known status. Settled orders are ignored.
+    pub fn track_with_status(&mut self, order_hash: B256, status: OrderStatus) {
+        if !is_settled(status) {
+            self.orders.insert(order_hash, status);
+        }
+    }
+
+    pub fn untrack(&mut self, order_hash: &B256) -> Option<OrderStatus> {
+        self.orders.remove(order_hash)
+    }
+
+    /// Returns the tracked orders, along with their last known status.
+    pub fn orders(&self) -> &HashMap<B256, OrderStatus> {
+        &self.orders
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Looks the tracked orders up, and returns the ones whose status changed. Settled orders
+    /// stop being tracked. The statuses are only updated once every batch was looked up, so that
+    /// a failed poll leaves them untouched and its changes are returned by the next one.
+    pub async fn poll(&mut self) -> Result<Vec<StatusChanged>, OrdersError> {
+        let order_hashes = self.orders.keys().copied().collect::<Vec<_>>();
+        let mut orders = vec![];
+
+        for batch in order_hashes.chunks(self.batch_size) {
+            let request = OrdersRequest {
+                chain_id: self.chain_id,
+                order_hashes: Some(batch.to_vec()),
+                ..Default::default()
+            };
+
+            let payload = self.orders_service.ready().await?.call(request).await?;
+
+            orders.extend(payload.orders);
+        }
+
+        Ok(orders
+            .into_iter()
+            .filter_map(|order| self.update(order))
+            .collect())
+    }
+
+    /// Marks the order of a fill seen on chain as filled, before the API reports it.
+    pub fn on_fill(&mut self, fill: &FillEvent) -> Option<StatusChanged> {
+        let from = self.orders.remove(&fill.order_hash)?;
+
+        Some(StatusChanged {
+            order_hash: fill.order_hash,
+            from,
+            to: OrderStatus::Filled,
+            tx_hash: Some(fill.tx),
+            settled_amounts: fill.settled_amounts.clone(),
+        })
+    }
+
+    fn update(&mut self, order: Order) -> Option<StatusChanged> {
+        let from = *self.orders.get(&order.order_hash)?;
+        let to = order.order_status;
+
+        if from == to {
+            return None;
+        }
+
+        if is_settled(to) {
+            self.orders.remove(&order.order_hash);
+        } else {
+            self.orders.insert(order.order_hash, to);
+        }
+
+        Some(StatusChanged {
+            order_hash: order.order_hash,
+            from,
+            to,
+            tx_hash: order.tx_hash.and_then(|tx_hash| tx_hash.parse().ok()),
+            settled_amounts: order.settled_amounts,
+        })
+    }
+
+    /// Streams the status changes of the tracked orders, polling the API every `poll_interval`
+    /// and applying the given fills as they come. Pass [`stream::empty`] to only rely on the
+    /// API. The stream ends once every order is settled.
+    pub fn into_stream<F>(
+        self,
+        poll_interval: Duration,
+        fills: F,
+    ) -> impl Stream<Item = Result<StatusChanged, OrdersError>>
+    where
+        F: Stream<Item = FillEvent> + Unpin,
+    {
+        let state = (self, fills.fuse(), Box::pin(sleep(Duration::ZERO)));
+
+        stream::unfold(
+            state,
+            move |(mut tracker, mut fills, mut next_poll)| async move {
+                if tracker.is_empty() {
+                    return None;
+                }
+
+                let next_fill = if fills.is_terminated() {
+                    Either::Left(future::pending())
+                } else {
+                    Either::Right(fills.next())
+                };
+
+                let changes = match future::select(next_poll.as_mut(), next_fill).await {
+                    Either::Left(_) => {
+                        next_poll.as_mut().reset(Instant::now() + poll_interval);
+
+                        tracker.poll().await
+                    }
+                    Either::Right((Some(fill), _)) => {
+                        Ok(tracker.on_fill(&fill).into_iter().collect())
+                    }
+                    Either::Right((None, _)) => Ok(vec![]),
+                };
+
+                Some((changes, (tracker, fills, next_poll)))
+            },
+        )
+        .flat_map(|changes| match changes {
+            Ok(changes) => Either::Right(iter(changes).map(Ok)),
+            Err(err) => Either::Left(once(async { Err(err) })),
+        })
+    }
+}
+
+/// Whether the order reached a final status. Orders lacking funds can still be filled once the
+/// swapper tops up its balance.
+fn is_settled(status: OrderStatus) -> bool {
+    !matches!(status, OrderStatus::Open | OrderStatus::InsufficientFunds)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use alloy::primitives::B256;
+    use futures::{stream, StreamExt};
+    use tower::service_fn;
+
+    use crate::{
+        types::{FillEvent, Order, OrderPayload, OrderResponseError, OrderStatus, OrdersRequest},
+        OrdersError,
+    };
+
+    use super::OrderTracker;
+
+    /// Answers lookups with the given statuses, and records the requested batches.
+    fn orders_service(
+        statuses: Vec<(B256, OrderStatus)>,
+        batches: Arc<Mutex<Vec<usize>>>,
+    ) -> impl tower::Service<
+        OrdersRequest,
+        Response = OrderPayload,
+        Error = OrdersError,
+        Future = impl std::future::Future<Output = Result<OrderPayload, OrdersError>>,
+    > {
+        service_fn(move |request: OrdersRequest| {
+            let order_hashes = request.order_hashes.unwrap_or_default();
+            batches.lock().unwrap().push(order_hashes.len());
+
+            let orders = statuses
+                .iter()
+                .filter(|(order_hash, _)| order_hashes.contains(order_hash))
+                .map(|(order_hash, order_status)| Order {
+                    order_hash: *order_hash,
+                    order_status: *order_status,
+                    tx_hash: matches!(order_status, OrderStatus::Filled)
+                        .then(|| B256::repeat_byte(0xff).to_string()),
+                    ..Default::default()
+                })
+                .collect();
+
+            async move {
+                Ok(OrderPayload {
+                    orders,
+                    cursor: None,
+                })
+            }
+        })
+    }
+
+    fn fill(order_hash: B256) -> FillEvent {
+        FillEvent {
+            order_hash,
+            tx: B256::repeat_byte(0xee),
+            block_number: 1,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_by_batches() {
+        let batches = Arc::new(Mutex::new(vec![]));
+        let statuses = vec![
+            (B256::repeat_byte(1), OrderStatus::Filled),
+            (B256::repeat_byte(2), OrderStatus::InsufficientFunds),
+            (B256::repeat_byte(3), OrderStatus::Open),
+        ];
+
+        let mut tracker =
+            OrderTracker::new(orders_service(statuses, batches.clone())).with_batch_size(2);
+
+        for order_hash in 1..=3 {
+            tracker.track(B256::repeat_byte(order_hash));
+        }
+
+        let mut changes = tracker.poll().await.unwrap();
+        changes.sort_by_key(|change| change.order_hash);
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].to, OrderStatus::Filled);
+        assert_eq!(changes[0].tx_hash, Some(B256::repeat_byte(0xff)));
+        assert_eq!(changes[1].to, OrderStatus::InsufficientFunds);
+
+        // the filled order isn't tracked anymore
+        assert_eq!(tracker.orders().len(), 2);
+        assert_eq!(*batches.lock().unwrap(), vec![2, 1]);
+
+        assert!(tracker.poll().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_keeps_changes() {
+        let requests = Arc::new(Mutex::new(0));
+
+        // every order is filled, but the second lookup fails once
+        let service = service_fn({
+            let requests = requests.clone();
+
+            move |request: OrdersRequest| {
+                let mut requests = requests.lock().unwrap();
+                *requests += 1;
+
+                let result = if *requests == 2 {
+                    let err = serde_json::from_str::<OrderResponseError>(
+                        r#"{"errorCode":"INTERNAL_ERROR"}"#,
+                    )
+                    .unwrap();
+
+                    Err(OrdersError::from(err))
+                } else {
+                    let orders = request
+                        .order_hashes
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|order_hash| Order {
+                            order_hash,
+                            order_status: OrderStatus::Filled,
+                            ..Default::default()
+                        })
+                        .collect();
+
+                    Ok(OrderPayload {
+                        orders,
+                        cursor: None,
+                    })
+                };
+
+                async move { result }
+            }
+        });
+
+        let mut tracker = OrderTracker::new(service).with_batch_size(1);
+        tracker.track(B256::repeat_byte(1));
+        tracker.track(B256::repeat_byte(2));
+
+        assert!(tracker.poll().await.is_err());
+
+        // the order of the first batch is still tracked, and its change is returned again
+        assert_eq!(tracker.orders().len(), 2);
+        assert_eq!(tracker.poll().await.unwrap().len(), 2);
+        assert!(tracker.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_on_fill() {
+        let batches = Arc::new(Mutex::new(vec![]));
+        let mut tracker = OrderTracker::new(orders_service(vec![], batches));
+        tracker.track(B256::repeat_byte(1));
+
+        let change = tracker.on_fill(&fill(B256::repeat_byte(1))).unwrap();
+
+        assert_eq!(change.from, OrderStatus::Open);
+        assert_eq!(change.to, OrderStatus::Filled);
+        assert_eq!(change.tx_hash, Some(B256::repeat_byte(0xee)));
+        assert!(tracker.is_empty());
+
+        // fills of other orders are ignored
+        assert!(tracker.on_fill(&fill(B256::repeat_byte(2))).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_ends_once_settled() {
+        let batches = Arc::new(Mutex::new(vec![]));
+        let statuses = vec![(B256::repeat_byte(1), OrderStatus::Cancelled)];
+
+        let mut tracker = OrderTracker::new(orders_service(statuses, batches));
+        tracker.track(B256::repeat_byte(1));
+        tracker.track(B256::repeat_byte(2));
+
+        let changes = tracker
+            .into_stream(
+                Duration::from_millis(1),
+                stream::iter(vec![fill(B256::repeat_byte(2))]),
+            )
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(Result::is_ok));
+    }
+}
@@ -3,13 +3,16 @@ use std::{
     task::{Context, Poll},
 };
 
-use futures::{Future, TryFutureExt};
-use reqwest::Client as HttpClient;
+use futures::{future::ready, Future, TryFutureExt};
+use reqwest::{Client as HttpClient, StatusCode};
 use thiserror::Error;
-use tower::Service;
+use tower::{BoxError, Service};
 
-use crate::types::{OrderPayload, OrderResponse, OrderResponseError, OrdersRequest};
+use crate::types::{
+    CursorError, OrderPayload, OrderResponse, OrderResponseError, OrdersRequest, OrdersRequestError,
+};
 
+#[derive(Clone)]
 pub struct OrdersService {
     http_client: HttpClient,
     url: String,
@@ -36,6 +39,11 @@ impl Service<OrdersRequest> for OrdersService {
     }
 
     fn call(&mut self, req: OrdersRequest) -> Self::Future {
+        // the API would reject the request anyway
+        if let Err(err) = req.validate() {
+            return Box::pin(ready(Err(err.into())));
+        }
+
         let fut = self
             .http_client
             .get(self.url.clone())
@@ -43,10 +51,25 @@ impl Service<OrdersRequest> for OrdersService {
             .send()
             .map_err(Into::into)
             .and_then(|response| {
+                let status = response.status();
+
                 response
                     .json::<OrderResponse>()
-                    .map_ok(|response| Result::from(response).map_err(Into::into))
-                    .unwrap_or_else(|err| Err(OrdersError::from(err)))
+                    .map_ok(move |response| {
+                        Result::from(response)
+                            .map_err(|err| OrdersError::UniswapX(err.with_status(status)))
+                    })
+                    .unwrap_or_else(move |err| {
+                        // e.g. the HTML error page of a gateway
+                        if status.is_success() {
+                            Err(OrdersError::from(err))
+                        } else {
+                            Err(OrdersError::Status {
+                                status,
+                                source: err,
+                            })
+                        }
+                    })
             });
 
         Box::pin(fut)
@@ -59,4 +82,42 @@ pub enum OrdersError {
     Send(#[from] reqwest::Error),
     #[error(transparent)]
     UniswapX(#[from] OrderResponseError),
+    #[error("The API answered with {status}: {source}")]
+    Status {
+        status: StatusCode,
+        source: reqwest::Error,
+    },
+    #[error("Invalid request: {0}")]
+    InvalidRequest(#[from] OrdersRequestError),
+    #[error("Can't resume after the last order: {0}")]
+    Cursor(#[from] CursorError),
+    #[error("The request timed out")]
+    Timeout,
+    #[error(transparent)]
+    Middleware(BoxError),
+}
+
+impl OrdersError {
+    /// Whether the request may succeed if sent again: timeouts, connection failures, rate
+    /// limits and server errors. Other errors fail the same way every time.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            OrdersError::Send(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err.is_request()
+                    || err.status().is_some_and(is_retryable_status)
+            }
+            OrdersError::UniswapX(err) => err.status().is_some_and(is_retryable_status),
+            OrdersError::Status { status, .. } => is_retryable_status(*status),
+            OrdersError::Timeout => true,
+            OrdersError::InvalidRequest(_)
+            | OrdersError::Cursor(_)
+            | OrdersError::Middleware(_) => false,
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
 }
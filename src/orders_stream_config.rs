@@ -0,0 +1,431 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, Future};
+use tokio::time::{sleep_until, Instant};
+use tower::{
+    retry::Policy,
+    timeout::{error::Elapsed, TimeoutLayer},
+    util::rng::{HasherRng, Rng},
+    BoxError, Layer, Service, ServiceBuilder, ServiceExt,
+};
+
+use crate::{
+    backoff::exponential_backoff,
+    types::{OrderPayload, OrdersRequest},
+    OrdersError,
+};
+
+/// How [`orders_stream_with_config`] polls the orders API.
+///
+/// [`orders_stream_with_config`]: crate::orders_stream_with_config
+#[derive(Debug, Clone, Copy)]
+pub struct OrdersStreamConfig {
+    /// The delay before polling again once the stream caught up with the API, or after a
+    /// failed request.
+    pub poll_interval: Duration,
+    /// The maximum number of requests sent per `rate_limit_period`, retries included.
+    pub rate_limit: u64,
+    pub rate_limit_period: Duration,
+    /// How failed requests are retried before being streamed as errors.
+    pub retry: RetryConfig,
+    /// The number of failed requests in a row after which the stream ends, if any.
+    pub max_consecutive_failures: Option<u32>,
+    /// How long a single request can take before it fails with [`OrdersError::Timeout`].
+    pub timeout: Option<Duration>,
+}
+
+impl OrdersStreamConfig {
+    /// Wraps the given service with the timeout, rate limit and retry layers of the config. The
+    /// service can itself be wrapped with other middleware beforehand.
+    pub fn layer<S>(
+        &self,
+        service: S,
+    ) -> impl Service<
+        OrdersRequest,
+        Response = OrderPayload,
+        Error = OrdersError,
+        Future = impl Future<Output = Result<OrderPayload, OrdersError>> + Send,
+    > + Send
+    where
+        S: Service<OrdersRequest, Response = OrderPayload, Error = OrdersError>
+            + Clone
+            + Send
+            + 'static,
+        S::Future: Send + 'static,
+    {
+        // retries go through the rate limit too, not to hammer an API which is throttling
+        ServiceBuilder::new()
+            .retry(OrdersRetryPolicy::new(self.retry))
+            .layer(RateLimitLayer::new(self.rate_limit, self.rate_limit_period))
+            .map_err(OrdersError::from_boxed)
+            .option_layer(self.timeout.map(TimeoutLayer::new))
+            .map_err(BoxError::from)
+            .service(service)
+    }
+}
+
+impl Default for OrdersStreamConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(3),
+            rate_limit: 1,
+            rate_limit_period: Duration::from_secs(3),
+            retry: RetryConfig::default(),
+            max_consecutive_failures: None,
+            timeout: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    /// The number of times a failed request is retried, 0 disables retries.
+    pub max_retries: usize,
+    /// The delay before the first retry, doubled after each failed retry.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// The ratio of the backoff randomly added to it, between 0 and 100.
+    pub jitter: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: 0.5,
+        }
+    }
+}
+
+/// Retries failed requests with an exponential backoff. Tower clones the policy for each
+/// request, so retries are counted per request.
+#[derive(Clone)]
+pub(crate) struct OrdersRetryPolicy {
+    config: RetryConfig,
+    retries: usize,
+    rng: HasherRng,
+}
+
+impl OrdersRetryPolicy {
+    pub(crate) fn new(config: RetryConfig) -> Self {
+        // out of range values are clamped, so that the jitter can't overflow
+        let jitter = if config.jitter.is_nan() {
+            0.0
+        } else {
+            config.jitter.clamp(0.0, 100.0)
+        };
+
+        Self {
+            config: RetryConfig { jitter, ..config },
+            retries: 0,
+            rng: HasherRng::new(),
+        }
+    }
+
+    /// Returns the delay before the next retry, randomly increased by up to `jitter` times itself.
+    fn backoff(&mut self) -> Duration {
+        let attempt = u32::try_from(self.retries).unwrap_or(u32::MAX);
+        let backoff = exponential_backoff(
+            self.config.initial_backoff,
+            self.config.max_backoff,
+            attempt,
+        );
+        let jitter = backoff.as_secs_f64() * self.config.jitter * self.rng.next_f64();
+
+        backoff.saturating_add(Duration::try_from_secs_f64(jitter).unwrap_or(Duration::MAX))
+    }
+}
+
+impl Policy<OrdersRequest, OrderPayload, OrdersError> for OrdersRetryPolicy {
+    type Future = tokio::time::Sleep;
+
+    fn retry(
+        &mut self,
+        _req: &mut OrdersRequest,
+        result: &mut Result<OrderPayload, OrdersError>,
+    ) -> Option<Self::Future> {
+        match result {
+            Err(err) if err.is_retryable() && self.retries < self.config.max_retries => {}
+            _ => return None,
+        }
+
+        self.retries += 1;
+
+        Some(tokio::time::sleep(self.backoff()))
+    }
+
+    fn clone_request(&mut self, req: &OrdersRequest) -> Option<OrdersRequest> {
+        Some(req.clone())
+    }
+}
+
+/// Limits the number of requests sent per period. Unlike tower's rate limit, the limit is shared
+/// by the clones of the service, so that it can sit below the retry layer.
+#[derive(Clone)]
+struct RateLimitLayer {
+    window: Arc<Mutex<RateWindow>>,
+}
+
+struct RateWindow {
+    rate: u64,
+    period: Duration,
+    start: Instant,
+    sent: u64,
+}
+
+impl RateLimitLayer {
+    fn new(rate: u64, period: Duration) -> Self {
+        Self {
+            window: Arc::new(Mutex::new(RateWindow {
+                rate: rate.max(1),
+                period,
+                start: Instant::now(),
+                sent: 0,
+            })),
+        }
+    }
+}
+
+impl RateWindow {
+    /// Books a slot for a request, and returns when it can be sent.
+    fn reserve(&mut self) -> Instant {
+        let now = Instant::now();
+
+        if self.start + self.period <= now {
+            self.start = now;
+            self.sent = 0;
+        }
+
+        // the window may already be booked in the future
+        if self.sent >= self.rate {
+            self.start += self.period;
+            self.sent = 0;
+        }
+
+        self.sent += 1;
+
+        self.start.max(now)
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            window: self.window.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct RateLimit<S> {
+    inner: S,
+    window: Arc<Mutex<RateWindow>>,
+}
+
+impl<S> Service<OrdersRequest> for RateLimit<S>
+where
+    S: Service<OrdersRequest> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // the inner service is only polled once the request is allowed through
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: OrdersRequest) -> Self::Future {
+        let send_at = self
+            .window
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .reserve();
+        let inner = self.inner.clone();
+
+        Box::pin(async move {
+            sleep_until(send_at).await;
+
+            inner.oneshot(req).await
+        })
+    }
+}
+
+impl OrdersError {
+    /// Recovers the error of a service wrapped with boxing middleware, such as a timeout.
+    fn from_boxed(err: BoxError) -> Self {
+        if err.is::<Elapsed>() {
+            return OrdersError::Timeout;
+        }
+
+        match err.downcast::<OrdersError>() {
+            Ok(err) => *err,
+            Err(err) => OrdersError::Middleware(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::{ready, Ready},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+        time::Duration,
+    };
+
+    use reqwest::StatusCode;
+    use tokio::time::Instant;
+    use tower::{service_fn, Service, ServiceExt};
+
+    use crate::{
+        types::{OrderPayload, OrderResponseError, OrdersRequest},
+        OrdersError,
+    };
+
+    use super::{OrdersStreamConfig, RetryConfig};
+
+    fn config(max_retries: usize) -> OrdersStreamConfig {
+        OrdersStreamConfig {
+            rate_limit: 100,
+            rate_limit_period: Duration::from_millis(1),
+            retry: RetryConfig {
+                max_retries,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(2),
+                jitter: 0.0,
+            },
+            timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        }
+    }
+
+    /// Fails the first `failures` requests it receives with the given status.
+    #[derive(Clone)]
+    struct FlakyService {
+        failures: usize,
+        status: StatusCode,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Service<OrdersRequest> for FlakyService {
+        type Response = OrderPayload;
+        type Error = OrdersError;
+        type Future = Ready<Result<OrderPayload, OrdersError>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _req: OrdersRequest) -> Self::Future {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                let err =
+                    serde_json::from_str::<OrderResponseError>(r#"{"errorCode":"INTERNAL_ERROR"}"#)
+                        .unwrap()
+                        .with_status(self.status);
+
+                return ready(Err(err.into()));
+            }
+
+            ready(Ok(OrderPayload {
+                orders: vec![],
+                cursor: None,
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_failed_requests() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let service = config(2).layer(FlakyService {
+            failures: 2,
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            calls: calls.clone(),
+        });
+
+        service.oneshot(OrdersRequest::default()).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let service = config(1).layer(FlakyService {
+            failures: 2,
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            calls: calls.clone(),
+        });
+
+        let err = service.oneshot(OrdersRequest::default()).await.unwrap_err();
+
+        assert!(matches!(err, OrdersError::UniswapX(_)));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_client_errors() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let service = config(2).layer(FlakyService {
+            failures: 1,
+            status: StatusCode::BAD_REQUEST,
+            calls: calls.clone(),
+        });
+
+        let err = service.oneshot(OrdersRequest::default()).await.unwrap_err();
+
+        assert!(matches!(err, OrdersError::UniswapX(_)));
+        assert!(!err.is_retryable());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retries_are_rate_limited() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let config = OrdersStreamConfig {
+            rate_limit: 1,
+            rate_limit_period: Duration::from_millis(50),
+            ..config(2)
+        };
+        let service = config.layer(FlakyService {
+            failures: 2,
+            status: StatusCode::TOO_MANY_REQUESTS,
+            calls: calls.clone(),
+        });
+
+        let start = Instant::now();
+        service.oneshot(OrdersRequest::default()).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let service = config(0).layer(service_fn(|_: OrdersRequest| async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            Ok::<_, OrdersError>(OrderPayload {
+                orders: vec![],
+                cursor: None,
+            })
+        }));
+
+        let err = service.oneshot(OrdersRequest::default()).await.unwrap_err();
+
+        assert!(matches!(err, OrdersError::Timeout));
+    }
+}
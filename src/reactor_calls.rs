@@ -0,0 +1,245 @@
+use alloy::{
+    primitives::{Address, Bytes, B256},
+    rpc::types::eth::{TransactionInput, TransactionRequest},
+    sol_types::SolCall,
+};
+use thiserror::Error;
+
+pub use crate::reactor_contract::ExclusiveDutchOrderReactorContract::SignedOrder;
+use crate::{
+    reactor_contract::ExclusiveDutchOrderReactorContract::{
+        executeBatchCall, executeBatchWithCallbackCall, executeCall, executeWithCallbackCall,
+    },
+    types::{Order, OrderDecodeError},
+};
+
+impl From<&Order> for SignedOrder {
+    fn from(order: &Order) -> Self {
+        Self {
+            order: order.encoded_order.clone(),
+            sig: order.signature.clone(),
+        }
+    }
+}
+
+/// Builds a call filling one or more API orders through their reactor.
+#[derive(Debug, Clone, Default)]
+pub struct ExecuteBuilder {
+    orders: Vec<Order>,
+    callback_data: Option<Bytes>,
+}
+
+impl ExecuteBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn order(mut self, order: Order) -> Self {
+        self.orders.push(order);
+        self
+    }
+
+    pub fn orders(mut self, orders: impl IntoIterator<Item = Order>) -> Self {
+        self.orders.extend(orders);
+        self
+    }
+
+    /// Fills the orders through the `WithCallback` entry points, which call back the filler's
+    /// `reactorCallback` with the given data.
+    pub fn callback_data(mut self, callback_data: impl Into<Bytes>) -> Self {
+        self.callback_data = Some(callback_data.into());
+        self
+    }
+
+    /// Checks every order is filled through the same reactor, and builds the call.
+    pub fn build(self) -> Result<ExecuteCall, ExecuteError> {
+        let mut reactor = None;
+
+        for order in &self.orders {
+            let order_reactor = order
+                .reactor()
+                .map_err(|source| ExecuteError::InvalidOrder {
+                    order_hash: order.order_hash,
+                    source,
+                })?;
+
+            match reactor {
+                None => reactor = Some(order_reactor),
+                Some(expected) if expected != order_reactor => {
+                    return Err(ExecuteError::ReactorMismatch {
+                        order_hash: order.order_hash,
+                        expected,
+                        found: order_reactor,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        let Some(reactor) = reactor else {
+            return Err(ExecuteError::NoOrders);
+        };
+
+        Ok(ExecuteCall {
+            reactor,
+            orders: self.orders.iter().map(SignedOrder::from).collect(),
+            callback_data: self.callback_data,
+        })
+    }
+}
+
+/// A call to one of the reactor's `execute` entry points.
+#[derive(Debug, Clone)]
+pub struct ExecuteCall {
+    pub reactor: Address,
+    pub orders: Vec<SignedOrder>,
+    pub callback_data: Option<Bytes>,
+}
+
+impl ExecuteCall {
+    /// ABI encodes the call, picking the entry point from the number of orders and whether the
+    /// filler uses a callback.
+    pub fn calldata(&self) -> Bytes {
+        let calldata = match (self.orders.as_slice(), &self.callback_data) {
+            ([order], None) => executeCall {
+                order: order.clone(),
+            }
+            .abi_encode(),
+            (orders, None) => executeBatchCall {
+                orders: orders.to_vec(),
+            }
+            .abi_encode(),
+            ([order], Some(callback_data)) => executeWithCallbackCall {
+                order: order.clone(),
+                callbackData: callback_data.clone(),
+            }
+            .abi_encode(),
+            (orders, Some(callback_data)) => executeBatchWithCallbackCall {
+                orders: orders.to_vec(),
+                callbackData: callback_data.clone(),
+            }
+            .abi_encode(),
+        };
+
+        calldata.into()
+    }
+
+    pub fn into_transaction_request(self) -> TransactionRequest {
+        let calldata = self.calldata();
+
+        TransactionRequest::default()
+            .to(self.reactor)
+            .input(TransactionInput::new(calldata))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ExecuteError {
+    #[error("No orders to execute")]
+    NoOrders,
+    #[error("Order {order_hash} can't be decoded: {source}")]
+    InvalidOrder {
+        order_hash: B256,
+        source: OrderDecodeError,
+    },
+    #[error("Order {order_hash} is filled through {found}, not {expected}")]
+    ReactorMismatch {
+        order_hash: B256,
+        expected: Address,
+        found: Address,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{address, Bytes},
+        sol_types::{SolCall, SolValue},
+    };
+
+    use crate::{
+        fixtures::{DUTCH_V1_ORDER, DUTCH_V1_SIGNATURE},
+        reactor_contract::ExclusiveDutchOrderReactorContract::{
+            executeBatchCall, executeCall, executeWithCallbackCall,
+        },
+        types::{DutchOrderV1, Order, OrderType},
+    };
+
+    use super::{ExecuteBuilder, ExecuteError};
+
+    fn order() -> Order {
+        Order {
+            chain_id: 1,
+            order_type: OrderType::Dutch,
+            encoded_order: DUTCH_V1_ORDER.parse().unwrap(),
+            signature: DUTCH_V1_SIGNATURE.parse().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_execute() {
+        let call = ExecuteBuilder::new().order(order()).build().unwrap();
+
+        assert_eq!(
+            call.reactor,
+            address!("e80bf394d190851e215d5f67b67f8f5a52783f1e")
+        );
+
+        let decoded = executeCall::abi_decode(&call.calldata(), true).unwrap();
+
+        assert_eq!(decoded.order.order, order().encoded_order);
+        assert_eq!(decoded.order.sig, order().signature);
+    }
+
+    #[test]
+    fn test_execute_batch() {
+        let call = ExecuteBuilder::new()
+            .orders([order(), order()])
+            .build()
+            .unwrap();
+
+        let decoded = executeBatchCall::abi_decode(&call.calldata(), true).unwrap();
+
+        assert_eq!(decoded.orders.len(), 2);
+    }
+
+    #[test]
+    fn test_execute_with_callback() {
+        let call = ExecuteBuilder::new()
+            .order(order())
+            .callback_data(Bytes::from_static(&[1, 2, 3]))
+            .build()
+            .unwrap();
+
+        let decoded = executeWithCallbackCall::abi_decode(&call.calldata(), true).unwrap();
+
+        assert_eq!(decoded.callbackData, Bytes::from_static(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn test_reactor_mismatch() {
+        let mut other = DutchOrderV1::abi_decode(&order().encoded_order, true).unwrap();
+        other.info.reactor = address!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4");
+
+        let other = Order {
+            encoded_order: other.abi_encode().into(),
+            ..order()
+        };
+
+        let err = ExecuteBuilder::new()
+            .orders([order(), other])
+            .build()
+            .unwrap_err();
+
+        assert!(matches!(err, ExecuteError::ReactorMismatch { .. }));
+    }
+
+    #[test]
+    fn test_no_orders() {
+        assert!(matches!(
+            ExecuteBuilder::new().build().unwrap_err(),
+            ExecuteError::NoOrders
+        ));
+    }
+}
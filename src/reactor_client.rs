@@ -1,46 +1,73 @@
-use std::sync::Arc;
+use std::{
+    collections::VecDeque,
+    ops::RangeInclusive,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
 
-use alloy::primitives::Address;
+use alloy::primitives::{Address, Bytes, B256, U256, U64};
 use alloy::{
     network::Network,
     providers::{Provider, RootProvider},
     pubsub::PubSubFrontend,
     rpc::{
-        json_rpc::{Id, Request, RequestMeta, ResponsePayload},
+        json_rpc::{ErrorPayload, Id, Request, RequestMeta, ResponsePayload},
         types::eth::{
             pubsub::{Params, SubscriptionKind},
-            BlockNumberOrTag, Filter, Log,
+            state::StateOverride,
+            BlockNumberOrTag, BlockTransactionsKind, Filter, Log, TransactionRequest,
         },
     },
-    sol,
     sol_types::SolEvent,
-    transports::Transport,
+    transports::{Transport, TransportError},
 };
-use anyhow::{anyhow, bail, Result};
 use futures::{
+    future::Either,
     stream::{self, BoxStream},
-    StreamExt,
+    Stream, StreamExt, TryStreamExt,
 };
-use tracing::error;
-
-use crate::{reactor_config::ReactorConfig, types::FillEvent};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use thiserror::Error;
+use tracing::{error, warn};
 
-sol!(
-    ExclusiveDutchOrderReactorContract,
-    "abi/exclusive_dutch_order_reactor.json"
-);
+use crate::{
+    block_range::{chunk_range, is_range_too_large, is_rate_limited, BackfillConfig},
+    confirmations::confirm_fills,
+    reactor_calls::{ExecuteBuilder, ExecuteError},
+    reactor_config::{ReactorConfig, ReactorConfigError, ReactorDeployment},
+    reactor_contract::ExclusiveDutchOrderReactorContract,
+    reactor_error::ReactorError,
+    resubscribe::{ResubscribeConfig, SeenFills},
+    types::{
+        needs_resolution, FillEvent, FillNotification, Order, OrderDecodeError, OrderResolution,
+        SettleError,
+    },
+};
 
 pub struct ReactorClient {
-    reactor_contract_address: Address,
+    config: ReactorConfig,
 }
 
 impl ReactorClient {
-    pub fn new(chain_id: u64) -> Self {
-        let config = ReactorConfig::new(chain_id);
+    /// Creates a client watching every known reactor deployed on the given chain.
+    pub fn new(chain_id: u64) -> Result<Self, ReactorConfigError> {
+        Ok(Self::with_config(ReactorConfig::new(chain_id)?))
+    }
 
-        Self {
-            reactor_contract_address: config.address,
-        }
+    /// Creates a client watching the reactors of the given config, which can be narrowed down
+    /// to a subset of the known reactors.
+    pub fn with_config(config: ReactorConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &ReactorConfig {
+        &self.config
+    }
+
+    pub fn reactors(&self) -> &[ReactorDeployment] {
+        &self.config.reactors
     }
 
     pub async fn get_fill_events<B, T, N>(
@@ -48,7 +75,7 @@ impl ReactorClient {
         provider: Arc<RootProvider<T, N>>,
         from_block: B,
         to_block: Option<B>,
-    ) -> Result<Vec<FillEvent>>
+    ) -> Result<Vec<FillEvent>, ReactorClientError>
     where
         B: Into<BlockNumberOrTag>,
         T: Transport + Clone,
@@ -57,14 +84,14 @@ impl ReactorClient {
         let filter = Filter::new()
             .from_block(from_block)
             .to_block(to_block.map(|b| b.into()).unwrap_or_default())
-            .address(self.reactor_contract_address)
+            .address(self.config.addresses())
             .event(ExclusiveDutchOrderReactorContract::Fill::SIGNATURE);
 
         let fill_event_logs = provider.get_logs(&filter).await?;
         let mut events = vec![];
 
         for log in fill_event_logs.into_iter().filter(|l| !l.removed) {
-            let fill = decode_fill_event(log)?;
+            let fill = decode_fill_event(log, &self.config)?;
 
             events.push(fill);
         }
@@ -72,75 +99,712 @@ impl ReactorClient {
         Ok(events)
     }
 
+    /// Streams the fill events emitted between the two blocks included, in block order. The
+    /// range is queried by chunks, several at a time. Queries the provider rejects as too large
+    /// are halved until they go through, and rate limited ones are retried after a backoff.
+    pub fn backfill_fill_events<T, N>(
+        &self,
+        provider: Arc<RootProvider<T, N>>,
+        from_block: u64,
+        to_block: u64,
+        config: BackfillConfig,
+    ) -> BoxStream<'static, Result<FillEvent, ReactorClientError>>
+    where
+        T: Transport + Clone,
+        N: Network,
+    {
+        let filter = Filter::new()
+            .address(self.config.addresses())
+            .event(ExclusiveDutchOrderReactorContract::Fill::SIGNATURE);
+
+        backfill_stream(
+            provider,
+            filter,
+            self.config.clone(),
+            from_block,
+            to_block,
+            config,
+        )
+    }
+
+    /// Simulates filling the given orders from `filler` with `eth_call`, optionally overriding
+    /// state such as the filler's balances and approvals. The orders must be settled by one of
+    /// the watched reactors. Reverts are decoded into a [`ReactorError`], any other RPC failure
+    /// is returned as an error. Once the call succeeds, the gas used is estimated with
+    /// `eth_estimateGas` under the same overrides.
+    pub async fn simulate_fill<T, N>(
+        &self,
+        provider: Arc<RootProvider<T, N>>,
+        orders: impl IntoIterator<Item = Order>,
+        filler: Address,
+        callback_data: Option<Bytes>,
+        overrides: Option<StateOverride>,
+    ) -> Result<FillSimulation, ReactorClientError>
+    where
+        T: Transport + Clone,
+        N: Network,
+        N::TransactionRequest: From<TransactionRequest>,
+    {
+        let mut builder = ExecuteBuilder::new().orders(orders);
+
+        if let Some(callback_data) = callback_data {
+            builder = builder.callback_data(callback_data);
+        }
+
+        let call = builder.build()?;
+
+        if !self.config.addresses().contains(&call.reactor) {
+            return Err(ReactorClientError::UnknownReactor(call.reactor));
+        }
+
+        let tx = N::TransactionRequest::from(call.into_transaction_request().from(filler));
+        let overrides = overrides.unwrap_or_default();
+
+        if let Err(err) = provider.call(&tx).overrides(&overrides).await {
+            return match ReactorError::from_rpc_error(&err) {
+                Some(reason) => Ok(FillSimulation::Reverted(reason)),
+                None => Err(err.into()),
+            };
+        }
+
+        let gas_used = provider.estimate_gas(&tx).overrides(&overrides).await?;
+
+        Ok(FillSimulation::Success { gas_used })
+    }
+
+    /// Fetches the receipt of the fill's transaction, and decodes the amounts it settled for
+    /// the given order. Native outputs, and outputs sharing a token and a recipient, are settled
+    /// from the order resolved at the fill's block.
+    pub async fn settle_fill_event<T>(
+        &self,
+        provider: Arc<RootProvider<T>>,
+        fill: &mut FillEvent,
+        order: &Order,
+    ) -> Result<(), ReactorClientError>
+    where
+        T: Transport + Clone,
+    {
+        let receipt = provider
+            .get_transaction_receipt(fill.tx)
+            .await?
+            .ok_or(ReactorClientError::MissingReceipt(fill.tx))?;
+
+        let resolved = if needs_resolution(order) {
+            let block = provider
+                .get_block_by_hash(fill.block_hash, BlockTransactionsKind::Hashes)
+                .await?
+                .ok_or(ReactorClientError::MissingBlock(fill.block_hash))?;
+
+            let base_fee = block.header.base_fee_per_gas.unwrap_or_default() as u128;
+            let priority_fee = receipt.effective_gas_price.saturating_sub(base_fee);
+
+            match order.resolve(
+                block.header.timestamp,
+                block.header.number,
+                U256::from(base_fee),
+                U256::from(priority_fee),
+                fill.filler,
+            )? {
+                OrderResolution::Resolved(resolved) => Some(resolved),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        fill.settle(order, receipt.inner.logs(), resolved.as_ref())?;
+
+        Ok(())
+    }
+
+    /// Streams the fills of the watched reactors as they're included in blocks. Fills included
+    /// in blocks which are later reorged out are streamed again as
+    /// [`FillNotification::Reverted`].
     pub async fn get_fill_events_stream(
         &self,
         front_end: &PubSubFrontend,
         id: Id,
-    ) -> Result<BoxStream<Result<FillEvent>>> {
-        let stringified_id = id.to_string();
-
-        let req = Request {
-            meta: RequestMeta::new("eth_subscribe".into(), id),
-            params: [
-                serde_json::to_value(SubscriptionKind::Logs)?,
-                serde_json::to_value(Params::Logs(Box::new(
-                    Filter::new()
-                        .address(self.reactor_contract_address)
-                        .event_signature(ExclusiveDutchOrderReactorContract::Fill::SIGNATURE_HASH),
-                )))?,
-            ],
-        };
+    ) -> Result<BoxStream<'static, Result<FillNotification, ReactorClientError>>, ReactorClientError>
+    {
+        let filter = Filter::new()
+            .address(self.config.addresses())
+            .event_signature(ExclusiveDutchOrderReactorContract::Fill::SIGNATURE_HASH);
 
-        let response = front_end
-            .send(req.serialize()?)
-            .await?
-            .deser_success()
-            .map_err(|_| anyhow!("The payload can't be deserialized"))?;
+        let (_, stream) = subscribe(front_end, id, logs_subscription_params(&filter)?).await?;
 
-        let subscription_id = match response.payload {
-            ResponsePayload::Success(subscription_id) => subscription_id,
-            ResponsePayload::Failure(err) => bail!(err),
-        };
+        let config = self.config.clone();
+
+        let stream = stream.map(move |value| decode_fill_notification(&value, &config));
+
+        Ok(stream.boxed())
+    }
+
+    /// Streams the fills of the watched reactors through a provider connected over a pubsub
+    /// transport, such as a websocket. The subscription is cancelled when the stream is dropped.
+    pub async fn subscribe_fill_events<P, T, N>(
+        &self,
+        provider: P,
+    ) -> Result<FillEventStream, FillStreamError>
+    where
+        P: Provider<T, N> + Clone + 'static,
+        T: Transport + Clone,
+        N: Network,
+    {
+        let filter = Filter::new()
+            .address(self.config.addresses())
+            .event_signature(ExclusiveDutchOrderReactorContract::Fill::SIGNATURE_HASH);
+
+        let subscription = provider.subscribe_logs(&filter).await?;
+        let subscription_id = *subscription.local_id();
 
-        let rx = front_end.get_subscription(subscription_id).await?;
-
-        let stream = stream::unfold(
-            (rx, stringified_id),
-            |(mut rx, stringified_id)| async move {
-                match rx.recv().await {
-                    Ok(value) => Some((value, (rx, stringified_id))),
-                    Err(err) => {
-                        error!("Subscription {stringified_id} ended: {err}");
-                        None
-                    }
+        let config = self.config.clone();
+
+        let stream = subscription.into_result_stream().map(
+            move |log| -> Result<FillNotification, FillStreamError> {
+                let log = log?;
+                let removed = log.removed;
+                let fill = decode_fill_event(log, &config)?;
+
+                if removed {
+                    Ok(FillNotification::Reverted(fill))
+                } else {
+                    Ok(FillNotification::Confirmed(fill))
                 }
             },
         );
 
-        let stream = stream.map(|value| {
-            {
-                serde_json::from_str::<Log>(value.get())
-                    .map_err(|err| anyhow!("Failed to deserialize log: {err}"))
+        Ok(FillEventStream {
+            inner: stream.boxed(),
+            unsubscribe: Some(Box::new(move || {
+                // the stream may be dropped outside of a runtime, in which case the
+                // subscription lives on until the connection closes
+                if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                    handle.spawn(async move {
+                        if let Err(err) = provider.unsubscribe(subscription_id).await {
+                            warn!("Failed to unsubscribe from {subscription_id}: {err}");
+                        }
+                    });
+                }
+            })),
+        })
+    }
+
+    /// Streams the fills of the watched reactors like
+    /// [`ReactorClient::get_fill_events_stream`], but survives dropped subscriptions: it
+    /// resubscribes with an exponential backoff, then backfills the fills emitted in the
+    /// meantime from the last block a fill was seen in. Fills seen twice are streamed once.
+    ///
+    /// Failed attempts are streamed as errors, the stream itself never ends.
+    pub fn get_resilient_fill_events_stream<T, N>(
+        &self,
+        front_end: PubSubFrontend,
+        provider: Arc<RootProvider<T, N>>,
+        config: ResubscribeConfig,
+    ) -> BoxStream<'static, Result<FillNotification, ReactorClientError>>
+    where
+        T: Transport + Clone,
+        N: Network,
+    {
+        let state = ResilientStream {
+            front_end,
+            provider,
+            config,
+            reactor_config: self.config.clone(),
+            filter: Filter::new()
+                .address(self.config.addresses())
+                .event_signature(ExclusiveDutchOrderReactorContract::Fill::SIGNATURE_HASH),
+            subscription: None,
+            next_id: 0,
+            attempt: 0,
+            queue: VecDeque::new(),
+            seen: SeenFills::default(),
+        };
+
+        stream::unfold(state, |mut state| async move {
+            let item = state.next().await;
+
+            Some((item, state))
+        })
+        .boxed()
+    }
+
+    /// Streams the fills of the watched reactors once `confirmations` blocks are built on top of
+    /// them, so that fills reorged out in the meantime are never streamed. Deeper reorgs are
+    /// still streamed as [`FillNotification::Reverted`]. The chain head is followed with a
+    /// `newHeads` subscription, requested with `id` suffixed by `-heads`.
+    pub async fn get_confirmed_fill_events_stream(
+        &self,
+        front_end: &PubSubFrontend,
+        id: Id,
+        confirmations: u64,
+    ) -> Result<BoxStream<'static, Result<FillNotification, ReactorClientError>>, ReactorClientError>
+    {
+        let heads_id = Id::String(format!("{id}-heads"));
+
+        let notifications = self.get_fill_events_stream(front_end, id).await?;
+
+        let (_, heads) = subscribe(
+            front_end,
+            heads_id,
+            vec![serde_json::to_value(SubscriptionKind::NewHeads)?],
+        )
+        .await?;
+
+        let heads = heads.filter_map(|value| async move {
+            match serde_json::from_str::<HeadNumber>(value.get()) {
+                Ok(head) => Some(head.number.to::<u64>()),
+                Err(err) => {
+                    error!("Failed to deserialize head: {err}");
+                    None
+                }
             }
-            .and_then(|log| {
-                decode_fill_event(log).map_err(|err| anyhow!("Failed to decode fill event: {err}"))
-            })
         });
 
-        Ok(stream.boxed())
+        Ok(confirm_fills(notifications, heads, confirmations).boxed())
     }
 }
 
-pub fn decode_fill_event(log: Log) -> Result<FillEvent> {
-    let ev = ExclusiveDutchOrderReactorContract::Fill::decode_log_data(log.data(), true)?;
+/// The stream returned by [`ReactorClient::subscribe_fill_events`].
+pub struct FillEventStream {
+    inner: BoxStream<'static, Result<FillNotification, FillStreamError>>,
+    unsubscribe: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl Stream for FillEventStream {
+    type Item = Result<FillNotification, FillStreamError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+impl Drop for FillEventStream {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
+        }
+    }
+}
 
-    let fill = FillEvent::new(
-        ev.orderHash,
-        ev.filler,
-        ev.swapper,
-        log.transaction_hash.unwrap(),
-        log.block_number.unwrap(),
+#[derive(Error, Debug)]
+pub enum FillStreamError {
+    #[error("Subscription failed: {0}")]
+    Subscription(#[from] TransportError),
+    #[error("Failed to deserialize log: {0}")]
+    Deserialization(#[from] serde_json::Error),
+    #[error("Failed to decode fill event: {0}")]
+    Decoding(#[from] ReactorClientError),
+}
+
+#[derive(Error, Debug)]
+pub enum ReactorClientError {
+    #[error("RPC request failed: {0}")]
+    Transport(#[from] TransportError),
+    #[error("The subscription was rejected: {0}")]
+    Subscription(ErrorPayload),
+    #[error("Failed to (de)serialize a JSON-RPC payload: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to decode fill event: {0}")]
+    Decode(#[from] alloy::sol_types::Error),
+    #[error("The fill log is pending, it has no block yet")]
+    PendingLog { tx: Option<B256> },
+    #[error("The fill log has no {0}")]
+    MissingLogField(&'static str),
+    #[error("The receipt of {0} can't be found")]
+    MissingReceipt(B256),
+    #[error("The block {0} can't be found")]
+    MissingBlock(B256),
+    #[error("The order can't be decoded: {0}")]
+    Order(#[from] OrderDecodeError),
+    #[error("The fill can't be settled: {0}")]
+    Settle(#[from] SettleError),
+    #[error("The fill can't be built: {0}")]
+    Execute(#[from] ExecuteError),
+    #[error("The orders are settled by {0}, which isn't a watched reactor")]
+    UnknownReactor(Address),
+}
+
+/// The state of [`ReactorClient::get_resilient_fill_events_stream`].
+struct ResilientStream<T, N: Network> {
+    front_end: PubSubFrontend,
+    provider: Arc<RootProvider<T, N>>,
+    config: ResubscribeConfig,
+    reactor_config: ReactorConfig,
+    filter: Filter,
+    subscription: Option<BoxStream<'static, Box<RawValue>>>,
+    next_id: u64,
+    attempt: u32,
+    queue: VecDeque<Result<FillNotification, ReactorClientError>>,
+    seen: SeenFills,
+}
+
+impl<T, N> ResilientStream<T, N>
+where
+    T: Transport + Clone,
+    N: Network,
+{
+    async fn next(&mut self) -> Result<FillNotification, ReactorClientError> {
+        loop {
+            if let Some(item) = self.queue.pop_front() {
+                return item;
+            }
+
+            let Some(subscription) = &mut self.subscription else {
+                self.resubscribe().await?;
+
+                continue;
+            };
+
+            match subscription.next().await {
+                Some(value) => match decode_fill_notification(&value, &self.reactor_config) {
+                    Ok(notification) if self.seen.accept(&notification) => return Ok(notification),
+                    Ok(_) => {}
+                    Err(err) => return Err(err),
+                },
+                None => {
+                    warn!("The fill subscription ended, resubscribing");
+                    self.subscription = None;
+                }
+            }
+        }
+    }
+
+    /// Subscribes again after a backoff, then queues the fills missed since the last block
+    /// observed, up to the current head. The subscription is only kept once the backfill
+    /// succeeded, so that no fill is missed, and cancelled otherwise.
+    async fn resubscribe(&mut self) -> Result<(), ReactorClientError> {
+        if self.attempt > 0 {
+            tokio::time::sleep(self.config.backoff(self.attempt)).await;
+        }
+
+        self.attempt += 1;
+        self.next_id += 1;
+
+        let params = logs_subscription_params(&self.filter)?;
+        let (subscription_id, subscription) =
+            subscribe(&self.front_end, Id::Number(self.next_id), params).await?;
+
+        if let Err(err) = self.backfill().await {
+            if let Err(err) = self.front_end.unsubscribe(subscription_id) {
+                warn!("Failed to unsubscribe from {subscription_id}: {err}");
+            }
+
+            return Err(err);
+        }
+
+        self.subscription = Some(subscription);
+        self.attempt = 0;
+
+        Ok(())
+    }
+
+    /// Queues the fills emitted since the last block observed, up to the current head. Nothing
+    /// is queued unless the whole range could be queried.
+    async fn backfill(&mut self) -> Result<(), ReactorClientError> {
+        let head = self.provider.get_block_number().await?;
+
+        // nothing can be missed before the first subscription
+        if let Some(from_block) = self.seen.last_block() {
+            let fills = backfill_stream(
+                self.provider.clone(),
+                self.filter.clone(),
+                self.reactor_config.clone(),
+                from_block,
+                head,
+                self.config.backfill,
+            )
+            .try_collect::<Vec<_>>()
+            .await?;
+
+            for fill in fills {
+                let notification = FillNotification::Confirmed(fill);
+
+                if self.seen.accept(&notification) {
+                    self.queue.push_back(Ok(notification));
+                }
+            }
+        }
+
+        self.seen.observe(head);
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct HeadNumber {
+    number: U64,
+}
+
+fn logs_subscription_params(filter: &Filter) -> Result<Vec<serde_json::Value>, serde_json::Error> {
+    Ok(vec![
+        serde_json::to_value(SubscriptionKind::Logs)?,
+        serde_json::to_value(Params::Logs(Box::new(filter.clone())))?,
+    ])
+}
+
+/// Sends an `eth_subscribe` request, and streams the raw notifications of the subscription,
+/// along with its id.
+async fn subscribe(
+    front_end: &PubSubFrontend,
+    id: Id,
+    params: Vec<serde_json::Value>,
+) -> Result<(B256, BoxStream<'static, Box<RawValue>>), ReactorClientError> {
+    let stringified_id = id.to_string();
+
+    let req = Request {
+        meta: RequestMeta::new("eth_subscribe".into(), id),
+        params,
+    };
+
+    let response = front_end.send(req.serialize()?).await?;
+
+    let subscription_id = match response.payload {
+        ResponsePayload::Success(subscription_id) => {
+            serde_json::from_str::<B256>(subscription_id.get())?
+        }
+        ResponsePayload::Failure(err) => return Err(ReactorClientError::Subscription(err)),
+    };
+
+    let rx = front_end.get_subscription(subscription_id).await?;
+
+    let stream = stream::unfold(
+        (rx, stringified_id),
+        |(mut rx, stringified_id)| async move {
+            match rx.recv().await {
+                Ok(value) => Some((value, (rx, stringified_id))),
+                Err(err) => {
+                    error!("Subscription {stringified_id} ended: {err}");
+                    None
+                }
+            }
+        },
     );
 
-    Ok(fill)
+    Ok((subscription_id, stream.boxed()))
+}
+
+/// Streams the fill events of a block range in block order, querying it by chunks, several at a
+/// time.
+fn backfill_stream<T, N>(
+    provider: Arc<RootProvider<T, N>>,
+    filter: Filter,
+    reactor_config: ReactorConfig,
+    from_block: u64,
+    to_block: u64,
+    config: BackfillConfig,
+) -> BoxStream<'static, Result<FillEvent, ReactorClientError>>
+where
+    T: Transport + Clone,
+    N: Network,
+{
+    let reactor_config = Arc::new(reactor_config);
+
+    stream::iter(chunk_range(from_block, to_block, config.chunk_size))
+        .map(move |range| {
+            let provider = provider.clone();
+            let filter = filter.clone();
+            let reactor_config = reactor_config.clone();
+
+            async move {
+                get_fill_events_in_range(&provider, &filter, range, &reactor_config, &config).await
+            }
+        })
+        .buffered(config.concurrency.max(1))
+        .flat_map(|events| match events {
+            Ok(events) => Either::Left(stream::iter(events).map(Ok)),
+            Err(err) => Either::Right(stream::once(async { Err(err) })),
+        })
+        .boxed()
+}
+
+/// Fetches the fill events of a block range. Queries the provider rejects as too large are
+/// halved, and doubled again once they go through, up to the whole range. Rate limited queries
+/// are retried after a backoff.
+async fn get_fill_events_in_range<T, N>(
+    provider: &RootProvider<T, N>,
+    filter: &Filter,
+    range: RangeInclusive<u64>,
+    reactor_config: &ReactorConfig,
+    config: &BackfillConfig,
+) -> Result<Vec<FillEvent>, ReactorClientError>
+where
+    T: Transport + Clone,
+    N: Network,
+{
+    let (mut start, end) = (*range.start(), *range.end());
+    let max_size = (end - start).saturating_add(1);
+    let mut size = max_size;
+    let mut retries = 0;
+    let mut events = vec![];
+
+    loop {
+        let to_block = start.saturating_add(size - 1).min(end);
+        let filter = filter.clone().from_block(start).to_block(to_block);
+
+        match provider.get_logs(&filter).await {
+            Ok(logs) => {
+                for log in logs.into_iter().filter(|l| !l.removed) {
+                    events.push(decode_fill_event(log, reactor_config)?);
+                }
+
+                if to_block == end {
+                    return Ok(events);
+                }
+
+                start = to_block + 1;
+                size = size.saturating_mul(2).min(max_size);
+                retries = 0;
+            }
+            Err(err) if is_range_too_large(&err) && to_block > start => {
+                size = (to_block - start).div_ceil(2);
+            }
+            Err(err) if is_rate_limited(&err) && retries < config.max_retries => {
+                retries += 1;
+                tokio::time::sleep(config.backoff(retries)).await;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum FillSimulation {
+    /// The fill goes through, using an estimated `gas_used`.
+    Success {
+        gas_used: u64,
+    },
+    Reverted(ReactorError),
+}
+
+fn decode_fill_notification(
+    value: &RawValue,
+    config: &ReactorConfig,
+) -> Result<FillNotification, ReactorClientError> {
+    let log = serde_json::from_str::<Log>(value.get())?;
+    let removed = log.removed;
+
+    let fill = decode_fill_event(log, config)?;
+
+    if removed {
+        Ok(FillNotification::Reverted(fill))
+    } else {
+        Ok(FillNotification::Confirmed(fill))
+    }
+}
+
+/// Decodes a `Fill` log, tagged with the order type of its reactor when it settles a single
+/// type, see [`ReactorConfig::order_type`].
+pub fn decode_fill_event(
+    log: Log,
+    config: &ReactorConfig,
+) -> Result<FillEvent, ReactorClientError> {
+    let ev = ExclusiveDutchOrderReactorContract::Fill::decode_log_data(log.data(), true)?;
+    let reactor = log.address();
+    let order_type = config.order_type(reactor);
+
+    // logs of pending transactions have no block yet
+    let (Some(block_number), Some(block_hash)) = (log.block_number, log.block_hash) else {
+        return Err(ReactorClientError::PendingLog {
+            tx: log.transaction_hash,
+        });
+    };
+
+    Ok(FillEvent {
+        order_hash: ev.orderHash,
+        filler: ev.filler,
+        swapper: ev.swapper,
+        reactor,
+        order_type,
+        tx: log
+            .transaction_hash
+            .ok_or(ReactorClientError::MissingLogField("transaction hash"))?,
+        block_number,
+        block_hash,
+        log_index: log
+            .log_index
+            .ok_or(ReactorClientError::MissingLogField("log index"))?,
+        settled_amounts: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{address, Address, B256, U256},
+        rpc::types::eth::Log,
+        sol_types::SolEvent,
+    };
+
+    use crate::{
+        reactor_config::ReactorConfig, reactor_contract::ExclusiveDutchOrderReactorContract,
+        types::OrderType,
+    };
+
+    use super::{decode_fill_event, ReactorClientError};
+
+    fn fill_log(reactor: Address) -> Log {
+        let fill = ExclusiveDutchOrderReactorContract::Fill {
+            orderHash: B256::repeat_byte(1),
+            filler: Address::repeat_byte(2),
+            swapper: Address::repeat_byte(3),
+            nonce: U256::from(4),
+        };
+
+        Log {
+            inner: alloy::primitives::Log {
+                address: reactor,
+                data: fill.encode_log_data(),
+            },
+            block_hash: Some(B256::repeat_byte(5)),
+            block_number: Some(6),
+            block_timestamp: None,
+            transaction_hash: Some(B256::repeat_byte(7)),
+            transaction_index: Some(0),
+            log_index: Some(8),
+            removed: false,
+        }
+    }
+
+    #[test]
+    fn test_decode_fill_event() {
+        let config = ReactorConfig::new(1).unwrap();
+        let reactor = address!("00000011F84B9aa48e5f8aA8B9897600006289Be");
+
+        let fill = decode_fill_event(fill_log(reactor), &config).unwrap();
+
+        assert_eq!(fill.order_hash, B256::repeat_byte(1));
+        assert_eq!(fill.reactor, reactor);
+        assert_eq!(fill.order_type, Some(OrderType::DutchV2));
+        assert_eq!(fill.log_index, 8);
+    }
+
+    #[test]
+    fn test_decode_fill_event_of_shared_reactor() {
+        let config = ReactorConfig::new(1).unwrap();
+        let reactor = address!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4");
+
+        let fill = decode_fill_event(fill_log(reactor), &config).unwrap();
+
+        // the reactor settles both Dutch and limit orders
+        assert_eq!(fill.reactor, reactor);
+        assert_eq!(fill.order_type, None);
+    }
+
+    #[test]
+    fn test_decode_pending_fill_event() {
+        let config = ReactorConfig::new(1).unwrap();
+        let log = Log {
+            block_hash: None,
+            block_number: None,
+            ..fill_log(address!("00000011F84B9aa48e5f8aA8B9897600006289Be"))
+        };
+
+        let err = decode_fill_event(log, &config).unwrap_err();
+
+        assert!(matches!(
+            err,
+            ReactorClientError::PendingLog { tx: Some(tx) } if tx == B256::repeat_byte(7)
+        ));
+    }
 }
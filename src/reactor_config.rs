@@ -1,16 +1,226 @@
 use alloy::primitives::{address, Address};
+use thiserror::Error;
 
-pub struct ReactorConfig {
+use crate::types::OrderType;
+
+/// Permit2 is deployed at the same address on every supported chain.
+pub const PERMIT2_ADDRESS: Address = address!("000000000022D473030F116dDEE9F6B43aC78BA3");
+
+const UNISWAPX_COSIGNER: Address = address!("4449Cd34d1eb1FEDCF02A1Be3834FfDe8E6A6180");
+
+/// A reactor contract deployed on a given chain, along with the type of orders it settles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReactorDeployment {
+    pub order_type: OrderType,
     pub address: Address,
 }
 
+impl ReactorDeployment {
+    pub const fn new(order_type: OrderType, address: Address) -> Self {
+        Self {
+            order_type,
+            address,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReactorConfig {
+    pub chain_id: u64,
+    pub permit2: Address,
+    pub cosigner: Option<Address>,
+    pub reactors: Vec<ReactorDeployment>,
+}
+
 impl ReactorConfig {
-    pub fn new(chain_id: u64) -> Self {
-        match chain_id {
-            1 => Self {
-                address: address!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4"),
-            },
-            chain_id => unimplemented!("Chain {chain_id} not supported"),
+    pub fn new(chain_id: u64) -> Result<Self, ReactorConfigError> {
+        use OrderType::*;
+
+        let (reactors, cosigner) = match chain_id {
+            // Mainnet
+            1 => (
+                vec![
+                    ReactorDeployment::new(
+                        Dutch,
+                        address!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4"),
+                    ),
+                    ReactorDeployment::new(
+                        DutchV2,
+                        address!("00000011F84B9aa48e5f8aA8B9897600006289Be"),
+                    ),
+                    ReactorDeployment::new(
+                        Limit,
+                        address!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4"),
+                    ),
+                    ReactorDeployment::new(
+                        Relay,
+                        address!("0000000000A4e21E2597DCac987455c48b12edBF"),
+                    ),
+                ],
+                Some(UNISWAPX_COSIGNER),
+            ),
+            // Polygon
+            137 => (
+                vec![ReactorDeployment::new(
+                    Dutch,
+                    address!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4"),
+                )],
+                None,
+            ),
+            // Arbitrum
+            42161 => (
+                vec![
+                    ReactorDeployment::new(
+                        DutchV2,
+                        address!("1bd1aAdc9E230626C44a139d7E70d842749351eb"),
+                    ),
+                    ReactorDeployment::new(
+                        DutchV3,
+                        address!("B274d5F4b833b61B340b654d600A864fB604a87c"),
+                    ),
+                ],
+                Some(UNISWAPX_COSIGNER),
+            ),
+            // Base
+            8453 => (
+                vec![ReactorDeployment::new(
+                    Priority,
+                    address!("000000001Ec5656dcdB24D90DFa42742738De729"),
+                )],
+                None,
+            ),
+            // Unichain
+            130 => (
+                vec![ReactorDeployment::new(
+                    Priority,
+                    address!("00000006021a6Bce796be7ba509BBBA71e956e37"),
+                )],
+                None,
+            ),
+            // Sepolia
+            11155111 => (
+                vec![
+                    ReactorDeployment::new(
+                        Dutch,
+                        address!("D6c073F2A3b676B8f9002b276B618e0d8bA84Fad"),
+                    ),
+                    ReactorDeployment::new(
+                        DutchV2,
+                        address!("0e22B6638161A89533940Db590E67A52474bEBcd"),
+                    ),
+                    ReactorDeployment::new(
+                        Relay,
+                        address!("0000000000A4e21E2597DCac987455c48b12edBF"),
+                    ),
+                ],
+                Some(UNISWAPX_COSIGNER),
+            ),
+            chain_id => return Err(ReactorConfigError::UnsupportedChain(chain_id)),
+        };
+
+        Ok(Self {
+            chain_id,
+            permit2: PERMIT2_ADDRESS,
+            cosigner,
+            reactors,
+        })
+    }
+
+    /// Returns the address of the reactor settling the given order type, if it is deployed on
+    /// this chain.
+    pub fn reactor(&self, order_type: OrderType) -> Option<Address> {
+        self.reactors
+            .iter()
+            .find(|r| r.order_type == order_type)
+            .map(|r| r.address)
+    }
+
+    /// Returns the order type settled by the given reactor, if it is known on this chain and
+    /// settles a single type. The mainnet Dutch reactor also settles limit orders, so its fills
+    /// can't be told apart and `None` is returned.
+    pub fn order_type(&self, reactor: Address) -> Option<OrderType> {
+        match self.order_types(reactor).as_slice() {
+            [order_type] => Some(*order_type),
+            _ => None,
         }
     }
+
+    /// Returns every order type settled by the given reactor on this chain.
+    pub fn order_types(&self, reactor: Address) -> Vec<OrderType> {
+        self.reactors
+            .iter()
+            .filter(|r| r.address == reactor)
+            .map(|r| r.order_type)
+            .collect()
+    }
+
+    /// Returns the distinct addresses of every reactor deployed on this chain.
+    pub fn addresses(&self) -> Vec<Address> {
+        let mut addresses = Vec::with_capacity(self.reactors.len());
+
+        for reactor in &self.reactors {
+            if !addresses.contains(&reactor.address) {
+                addresses.push(reactor.address);
+            }
+        }
+
+        addresses
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ReactorConfigError {
+    #[error("Chain {0} not supported")]
+    UnsupportedChain(u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::address;
+
+    use crate::types::OrderType;
+
+    use super::{ReactorConfig, ReactorConfigError};
+
+    #[test]
+    fn test_arbitrum_reactors() {
+        let config = ReactorConfig::new(42161).unwrap();
+
+        assert_eq!(
+            config.reactor(OrderType::DutchV3),
+            Some(address!("B274d5F4b833b61B340b654d600A864fB604a87c"))
+        );
+        assert_eq!(config.reactor(OrderType::Dutch), None);
+    }
+
+    #[test]
+    fn test_addresses_are_deduplicated() {
+        let config = ReactorConfig::new(1).unwrap();
+
+        assert_eq!(config.addresses().len(), 3);
+    }
+
+    #[test]
+    fn test_shared_reactor_order_type() {
+        let config = ReactorConfig::new(1).unwrap();
+        let reactor = address!("6000da47483062A0D734Ba3dc7576Ce6A0B645C4");
+
+        assert_eq!(
+            config.order_types(reactor),
+            vec![OrderType::Dutch, OrderType::Limit]
+        );
+        assert_eq!(config.order_type(reactor), None);
+        assert_eq!(
+            config.order_type(address!("00000011F84B9aa48e5f8aA8B9897600006289Be")),
+            Some(OrderType::DutchV2)
+        );
+    }
+
+    #[test]
+    fn test_unsupported_chain() {
+        assert_eq!(
+            ReactorConfig::new(0).unwrap_err(),
+            ReactorConfigError::UnsupportedChain(0)
+        );
+    }
 }
@@ -0,0 +1,7 @@
+use alloy::sol;
+
+sol!(
+    #[derive(Debug)]
+    ExclusiveDutchOrderReactorContract,
+    "abi/exclusive_dutch_order_reactor.json"
+);
@@ -0,0 +1,306 @@
+use alloy::{
+    primitives::{Address, Bytes, U256},
+    rpc::json_rpc::RpcError,
+    sol,
+    sol_types::{GenericContractError, SolInterface},
+};
+use thiserror::Error;
+
+sol! {
+    /// The custom errors of every reactor version, and of the libraries they use.
+    interface IReactorErrors {
+        error DeadlinePassed();
+        error DeadlineBeforeEndTime();
+        error EndTimeBeforeStartTime();
+        error OrderEndTimeBeforeStartTime();
+        error IncorrectAmounts();
+        error InputAndOutputDecay();
+        error InsufficientEth();
+        error InvalidReactor();
+        error NativeTransferFailed();
+        error NoExclusiveOverride();
+        error DuplicateFeeOutput(address duplicateToken);
+        error FeeTooLarge(address token, uint256 amount, address recipient);
+        error InvalidFeeToken(address feeToken);
+        error InvalidCosignature();
+        error InvalidCosignerInput();
+        error InvalidCosignerOutput();
+        error InvalidDecayCurve();
+        error InvalidDeadline();
+        error OrderNotFillable();
+        error InputOutputScaled();
+        error InvalidGasPrice();
+    }
+
+    /// The Permit2 errors a fill can revert with.
+    interface IPermit2Errors {
+        error InvalidNonce();
+        error SignatureExpired(uint256 signatureDeadline);
+        error InvalidAmount(uint256 maxAmount);
+        error LengthMismatch();
+        error InvalidSignatureLength();
+        error InvalidSignature();
+        error InvalidSigner();
+        error InvalidContractSignature();
+        error AllowanceExpired(uint256 deadline);
+        error InsufficientAllowance(uint256 amount);
+    }
+}
+
+/// Why a reactor call reverted, decoded from its revert data.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ReactorError {
+    #[error("The order deadline has passed")]
+    DeadlinePassed,
+    #[error("The order deadline is before the decay end time")]
+    DeadlineBeforeEndTime,
+    #[error("The decay end time is before the decay start time")]
+    EndTimeBeforeStartTime,
+    #[error("The decay end time is before the decay start time")]
+    OrderEndTimeBeforeStartTime,
+    #[error("The input decays downward or an output decays upward")]
+    IncorrectAmounts,
+    #[error("Both the input and the outputs decay")]
+    InputAndOutputDecay,
+    #[error("Not enough ETH was sent to fill the native outputs")]
+    InsufficientEth,
+    #[error("The order is not meant for this reactor")]
+    InvalidReactor,
+    #[error("Failed to transfer ETH")]
+    NativeTransferFailed,
+    #[error("The order is exclusive to another filler")]
+    NoExclusiveOverride,
+    #[error("Several fee outputs for token {token}")]
+    DuplicateFeeOutput { token: Address },
+    #[error("Fee of {amount} {token} to {recipient} is too large")]
+    FeeTooLarge {
+        token: Address,
+        amount: U256,
+        recipient: Address,
+    },
+    #[error("Token {token} can't be used to pay fees")]
+    InvalidFeeToken { token: Address },
+    #[error("The cosignature is invalid")]
+    InvalidCosignature,
+    #[error("The cosigner input override is larger than the input")]
+    InvalidCosignerInput,
+    #[error("The cosigner output overrides are smaller than the outputs")]
+    InvalidCosignerOutput,
+    #[error("The decay curve is invalid")]
+    InvalidDecayCurve,
+    #[error("The order deadline is invalid")]
+    InvalidDeadline,
+    #[error("The order can't be filled yet")]
+    OrderNotFillable,
+    #[error("Both the input and the outputs scale with the priority fee")]
+    InputOutputScaled,
+    #[error("The gas price is below the base fee")]
+    InvalidGasPrice,
+    #[error("Permit2: the nonce was already used")]
+    InvalidNonce,
+    #[error("Permit2: the signature expired at {deadline}")]
+    SignatureExpired { deadline: U256 },
+    #[error("Permit2: the amount exceeds the permitted {max_amount}")]
+    InvalidAmount { max_amount: U256 },
+    #[error("Permit2: length mismatch")]
+    LengthMismatch,
+    #[error("Permit2: the signature length is invalid")]
+    InvalidSignatureLength,
+    #[error("Permit2: the signature is invalid")]
+    InvalidSignature,
+    #[error("Permit2: the signature is not from the swapper")]
+    InvalidSigner,
+    #[error("Permit2: the contract signature is invalid")]
+    InvalidContractSignature,
+    #[error("Permit2: the allowance expired at {deadline}")]
+    AllowanceExpired { deadline: U256 },
+    #[error("Permit2: the allowance of {amount} is insufficient")]
+    InsufficientAllowance { amount: U256 },
+    #[error("Reverted: {0}")]
+    Revert(String),
+    #[error("Panicked with code {0}")]
+    Panic(U256),
+    #[error("Unknown revert data: {0}")]
+    Unknown(Bytes),
+}
+
+impl ReactorError {
+    /// Decodes the revert data of a reactor call.
+    pub fn decode(data: &[u8]) -> Self {
+        if let Ok(err) = IReactorErrors::IReactorErrorsErrors::abi_decode(data, true) {
+            return err.into();
+        }
+
+        if let Ok(err) = IPermit2Errors::IPermit2ErrorsErrors::abi_decode(data, true) {
+            return err.into();
+        }
+
+        match GenericContractError::abi_decode(data, true) {
+            Ok(GenericContractError::Revert(revert)) => ReactorError::Revert(revert.reason),
+            Ok(GenericContractError::Panic(panic)) => ReactorError::Panic(panic.code),
+            Err(_) => ReactorError::Unknown(Bytes::copy_from_slice(data)),
+        }
+    }
+
+    /// Decodes the revert data of a failed `eth_call` or `eth_estimateGas`, if the node returned
+    /// any.
+    pub fn from_rpc_error<E>(err: &RpcError<E>) -> Option<Self> {
+        let data = err.as_error_resp()?.as_revert_data()?;
+
+        Some(Self::decode(&data))
+    }
+}
+
+impl From<IReactorErrors::IReactorErrorsErrors> for ReactorError {
+    fn from(err: IReactorErrors::IReactorErrorsErrors) -> Self {
+        use IReactorErrors::IReactorErrorsErrors as E;
+
+        match err {
+            E::DeadlinePassed(_) => ReactorError::DeadlinePassed,
+            E::DeadlineBeforeEndTime(_) => ReactorError::DeadlineBeforeEndTime,
+            E::EndTimeBeforeStartTime(_) => ReactorError::EndTimeBeforeStartTime,
+            E::OrderEndTimeBeforeStartTime(_) => ReactorError::OrderEndTimeBeforeStartTime,
+            E::IncorrectAmounts(_) => ReactorError::IncorrectAmounts,
+            E::InputAndOutputDecay(_) => ReactorError::InputAndOutputDecay,
+            E::InsufficientEth(_) => ReactorError::InsufficientEth,
+            E::InvalidReactor(_) => ReactorError::InvalidReactor,
+            E::NativeTransferFailed(_) => ReactorError::NativeTransferFailed,
+            E::NoExclusiveOverride(_) => ReactorError::NoExclusiveOverride,
+            E::DuplicateFeeOutput(err) => ReactorError::DuplicateFeeOutput {
+                token: err.duplicateToken,
+            },
+            E::FeeTooLarge(err) => ReactorError::FeeTooLarge {
+                token: err.token,
+                amount: err.amount,
+                recipient: err.recipient,
+            },
+            E::InvalidFeeToken(err) => ReactorError::InvalidFeeToken {
+                token: err.feeToken,
+            },
+            E::InvalidCosignature(_) => ReactorError::InvalidCosignature,
+            E::InvalidCosignerInput(_) => ReactorError::InvalidCosignerInput,
+            E::InvalidCosignerOutput(_) => ReactorError::InvalidCosignerOutput,
+            E::InvalidDecayCurve(_) => ReactorError::InvalidDecayCurve,
+            E::InvalidDeadline(_) => ReactorError::InvalidDeadline,
+            E::OrderNotFillable(_) => ReactorError::OrderNotFillable,
+            E::InputOutputScaled(_) => ReactorError::InputOutputScaled,
+            E::InvalidGasPrice(_) => ReactorError::InvalidGasPrice,
+        }
+    }
+}
+
+impl From<IPermit2Errors::IPermit2ErrorsErrors> for ReactorError {
+    fn from(err: IPermit2Errors::IPermit2ErrorsErrors) -> Self {
+        use IPermit2Errors::IPermit2ErrorsErrors as E;
+
+        match err {
+            E::InvalidNonce(_) => ReactorError::InvalidNonce,
+            E::SignatureExpired(err) => ReactorError::SignatureExpired {
+                deadline: err.signatureDeadline,
+            },
+            E::InvalidAmount(err) => ReactorError::InvalidAmount {
+                max_amount: err.maxAmount,
+            },
+            E::LengthMismatch(_) => ReactorError::LengthMismatch,
+            E::InvalidSignatureLength(_) => ReactorError::InvalidSignatureLength,
+            E::InvalidSignature(_) => ReactorError::InvalidSignature,
+            E::InvalidSigner(_) => ReactorError::InvalidSigner,
+            E::InvalidContractSignature(_) => ReactorError::InvalidContractSignature,
+            E::AllowanceExpired(err) => ReactorError::AllowanceExpired {
+                deadline: err.deadline,
+            },
+            E::InsufficientAllowance(err) => {
+                ReactorError::InsufficientAllowance { amount: err.amount }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{address, bytes, U256},
+        rpc::json_rpc::{ErrorPayload, RpcError},
+        sol_types::{Revert, SolError},
+    };
+
+    use super::{IPermit2Errors, IReactorErrors, ReactorError};
+
+    #[test]
+    fn test_decode_reactor_error() {
+        let data = IReactorErrors::FeeTooLarge {
+            token: address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+            amount: U256::from(100),
+            recipient: address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"),
+        }
+        .abi_encode();
+
+        assert_eq!(
+            ReactorError::decode(&data),
+            ReactorError::FeeTooLarge {
+                token: address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+                amount: U256::from(100),
+                recipient: address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"),
+            }
+        );
+        // `NoExclusiveOverride()`
+        assert_eq!(
+            ReactorError::decode(&bytes!("b9ec1e96")),
+            ReactorError::NoExclusiveOverride
+        );
+    }
+
+    #[test]
+    fn test_decode_permit2_error() {
+        // `InvalidNonce()`
+        assert_eq!(
+            ReactorError::decode(&bytes!("756688fe")),
+            ReactorError::InvalidNonce
+        );
+
+        let data = IPermit2Errors::SignatureExpired {
+            signatureDeadline: U256::from(1000),
+        }
+        .abi_encode();
+
+        assert_eq!(
+            ReactorError::decode(&data),
+            ReactorError::SignatureExpired {
+                deadline: U256::from(1000)
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_revert_string() {
+        let data = Revert::from("TRANSFER_FROM_FAILED").abi_encode();
+
+        assert_eq!(
+            ReactorError::decode(&data),
+            ReactorError::Revert("TRANSFER_FROM_FAILED".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_unknown_error() {
+        assert_eq!(
+            ReactorError::decode(&bytes!("deadbeef")),
+            ReactorError::Unknown(bytes!("deadbeef"))
+        );
+    }
+
+    #[test]
+    fn test_from_rpc_error() {
+        let payload: ErrorPayload = serde_json::from_str(
+            r#"{"code":3,"message":"execution reverted","data":"0x756688fe"}"#,
+        )
+        .unwrap();
+
+        let err = RpcError::<()>::ErrorResp(payload);
+
+        assert_eq!(
+            ReactorError::from_rpc_error(&err),
+            Some(ReactorError::InvalidNonce)
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use std::{collections::HashMap, time::Duration};
+
+use alloy::primitives::B256;
+
+use crate::{backoff::exponential_backoff, block_range::BackfillConfig, types::FillNotification};
+
+/// How [`ReactorClient::get_resilient_fill_events_stream`] recovers from dropped subscriptions.
+///
+/// [`ReactorClient::get_resilient_fill_events_stream`]: crate::ReactorClient::get_resilient_fill_events_stream
+#[derive(Debug, Clone, Copy)]
+pub struct ResubscribeConfig {
+    /// The delay before the first resubscription attempt, doubled after each failed attempt.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// How the blocks missed while resubscribing are queried.
+    pub backfill: BackfillConfig,
+}
+
+impl ResubscribeConfig {
+    /// Returns the delay before the given resubscription attempt, starting at 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        exponential_backoff(self.initial_backoff, self.max_backoff, attempt)
+    }
+}
+
+impl Default for ResubscribeConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            backfill: BackfillConfig::default(),
+        }
+    }
+}
+
+/// The number of blocks fills are remembered for, past the last block observed. Backfills start
+/// at the last block observed, so only reorgs deeper than this could lead to duplicates.
+const RETAINED_BLOCKS: u64 = 128;
+
+/// Remembers the recently streamed fills, so that the ones seen both by a backfill and by the
+/// live subscription are streamed once.
+#[derive(Debug, Default)]
+pub(crate) struct SeenFills {
+    fills: HashMap<(B256, B256), u64>,
+    last_block: Option<u64>,
+}
+
+impl SeenFills {
+    /// Returns whether the notification should be streamed, i.e. whether it confirms a new fill
+    /// or reverts one.
+    pub(crate) fn accept(&mut self, notification: &FillNotification) -> bool {
+        let fill = notification.fill();
+        let key = (fill.order_hash, fill.tx);
+
+        match notification {
+            FillNotification::Confirmed(_) => {
+                if self.fills.contains_key(&key) {
+                    return false;
+                }
+
+                self.fills.insert(key, fill.block_number);
+                self.observe(fill.block_number);
+
+                true
+            }
+            FillNotification::Reverted(_) => {
+                self.fills.remove(&key);
+
+                true
+            }
+        }
+    }
+
+    /// Records a block known to be processed, such as the head when subscribing, and forgets
+    /// the fills too old to be backfilled again.
+    pub(crate) fn observe(&mut self, block_number: u64) {
+        let last_block = self.last_block.unwrap_or_default().max(block_number);
+        self.last_block = Some(last_block);
+        self.fills
+            .retain(|_, block_number| *block_number + RETAINED_BLOCKS >= last_block);
+    }
+
+    /// Returns the highest block observed, either as the head when subscribing or as the block
+    /// of a fill.
+    pub(crate) fn last_block(&self) -> Option<u64> {
+        self.last_block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use alloy::primitives::B256;
+
+    use crate::types::{FillEvent, FillNotification};
+
+    use super::{ResubscribeConfig, SeenFills};
+
+    fn fill(order_hash: u8, block_number: u64) -> FillEvent {
+        FillEvent {
+            order_hash: B256::repeat_byte(order_hash),
+            tx: B256::repeat_byte(order_hash),
+            block_number,
+            block_hash: B256::with_last_byte(block_number as u8),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff() {
+        let config = ResubscribeConfig {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            ..Default::default()
+        };
+
+        assert_eq!(config.backoff(1), Duration::from_secs(1));
+        assert_eq!(config.backoff(3), Duration::from_secs(4));
+        assert_eq!(config.backoff(5), Duration::from_secs(10));
+        assert_eq!(config.backoff(100), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_deduplicate_fills() {
+        let mut seen = SeenFills::default();
+
+        assert!(seen.accept(&FillNotification::Confirmed(fill(1, 100))));
+        assert!(!seen.accept(&FillNotification::Confirmed(fill(1, 100))));
+        assert!(seen.accept(&FillNotification::Confirmed(fill(2, 101))));
+        assert_eq!(seen.last_block(), Some(101));
+    }
+
+    #[test]
+    fn test_observe_head() {
+        let mut seen = SeenFills::default();
+
+        seen.observe(200);
+        assert!(seen.accept(&FillNotification::Confirmed(fill(1, 150))));
+        assert_eq!(seen.last_block(), Some(200));
+
+        // fills too old to be backfilled again are forgotten
+        seen.observe(400);
+        assert!(seen.accept(&FillNotification::Confirmed(fill(1, 150))));
+    }
+
+    #[test]
+    fn test_accept_fill_again_after_revert() {
+        let mut seen = SeenFills::default();
+
+        assert!(seen.accept(&FillNotification::Confirmed(fill(1, 100))));
+        assert!(seen.accept(&FillNotification::Reverted(fill(1, 100))));
+        assert!(seen.accept(&FillNotification::Confirmed(fill(1, 100))));
+    }
+}
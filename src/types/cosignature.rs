@@ -0,0 +1,309 @@
+use alloy::{
+    primitives::{keccak256, Address, B256, U256},
+    sol_types::SolValue,
+};
+use thiserror::Error;
+
+use super::{
+    dutch_order::DutchOrderV2,
+    permit2::{verify_signer, SignatureVerificationError},
+    DutchOrder, DutchOrderV3,
+};
+
+impl DutchOrder {
+    /// Checks the cosigner data was signed by the order's cosigner, and that the reactor accepts
+    /// its overrides. V1 orders have no cosigner, so they are always valid.
+    pub fn verify_cosignature(&self, chain_id: u64) -> Result<(), CosignatureError> {
+        match self {
+            DutchOrder::V1(_) => Ok(()),
+            DutchOrder::V2(order) => order.verify_cosignature(chain_id),
+            DutchOrder::V3(order) => order.verify_cosignature(chain_id),
+        }
+    }
+}
+
+impl DutchOrderV2 {
+    /// Computes the digest the cosigner signs: the order hash, the chain id and the ABI encoded
+    /// cosigner data.
+    pub fn cosigner_digest(&self, chain_id: u64) -> B256 {
+        cosigner_digest(self.hash(), chain_id, self.cosignerData.abi_encode())
+    }
+
+    pub fn verify_cosignature(&self, chain_id: u64) -> Result<(), CosignatureError> {
+        if self.info.deadline < self.cosignerData.decayEndTime {
+            return Err(CosignatureError::DeadlineBeforeEndTime);
+        }
+
+        verify_cosigner(
+            self.cosigner_digest(chain_id),
+            &self.cosignature,
+            self.cosigner,
+        )?;
+
+        validate_overrides(
+            self.input.startAmount,
+            self.cosignerData.inputOverride,
+            self.outputs.iter().map(|output| output.startAmount),
+            &self.cosignerData.outputOverrides,
+        )
+    }
+}
+
+impl DutchOrderV3 {
+    /// Computes the digest the cosigner signs: the order hash, the chain id and the ABI encoded
+    /// cosigner data.
+    pub fn cosigner_digest(&self, chain_id: u64) -> B256 {
+        cosigner_digest(self.hash(), chain_id, self.cosignerData.abi_encode())
+    }
+
+    pub fn verify_cosignature(&self, chain_id: u64) -> Result<(), CosignatureError> {
+        verify_cosigner(
+            self.cosigner_digest(chain_id),
+            &self.cosignature,
+            self.cosigner,
+        )?;
+
+        validate_overrides(
+            self.input.startAmount,
+            self.cosignerData.inputOverride,
+            self.outputs.iter().map(|output| output.startAmount),
+            &self.cosignerData.outputOverrides,
+        )
+    }
+}
+
+fn cosigner_digest(order_hash: B256, chain_id: u64, encoded_cosigner_data: Vec<u8>) -> B256 {
+    keccak256((order_hash, U256::from(chain_id), encoded_cosigner_data).abi_encode_packed())
+}
+
+fn verify_cosigner(
+    digest: B256,
+    cosignature: &[u8],
+    cosigner: Address,
+) -> Result<(), CosignatureError> {
+    // the reactor only supports 65 bytes cosignatures
+    if cosignature.len() != 65 {
+        return Err(CosignatureError::MalformedCosignature(cosignature.len()));
+    }
+
+    Ok(verify_signer(digest, cosignature, cosigner)?)
+}
+
+/// Checks the cosigner overrides the same way the reactor does: the input can only be improved
+/// downward, and the outputs upward.
+pub(crate) fn validate_overrides(
+    input_start_amount: U256,
+    input_override: U256,
+    output_start_amounts: impl ExactSizeIterator<Item = U256>,
+    output_overrides: &[U256],
+) -> Result<(), CosignatureError> {
+    if !input_override.is_zero() && input_override > input_start_amount {
+        return Err(CosignatureError::InvalidCosignerInput {
+            input_override,
+            start_amount: input_start_amount,
+        });
+    }
+
+    if output_start_amounts.len() != output_overrides.len() {
+        return Err(CosignatureError::OutputOverridesMismatch {
+            outputs: output_start_amounts.len(),
+            overrides: output_overrides.len(),
+        });
+    }
+
+    for (index, (start_amount, output_override)) in
+        output_start_amounts.zip(output_overrides).enumerate()
+    {
+        if !output_override.is_zero() && *output_override < start_amount {
+            return Err(CosignatureError::InvalidCosignerOutput {
+                index,
+                output_override: *output_override,
+                start_amount,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies the cosigner overrides to the start amounts, once checked.
+pub(crate) fn apply_overrides(
+    input_start_amount: U256,
+    input_override: U256,
+    output_start_amounts: Vec<U256>,
+    output_overrides: &[U256],
+) -> Result<(U256, Vec<U256>), CosignatureError> {
+    validate_overrides(
+        input_start_amount,
+        input_override,
+        output_start_amounts.iter().copied(),
+        output_overrides,
+    )?;
+
+    let input_start_amount = if input_override.is_zero() {
+        input_start_amount
+    } else {
+        input_override
+    };
+
+    let output_start_amounts = output_start_amounts
+        .into_iter()
+        .zip(output_overrides)
+        .map(|(start_amount, output_override)| {
+            if output_override.is_zero() {
+                start_amount
+            } else {
+                *output_override
+            }
+        })
+        .collect();
+
+    Ok((input_start_amount, output_start_amounts))
+}
+
+#[derive(Error, Debug)]
+pub enum CosignatureError {
+    #[error("Expected a 65 bytes cosignature, got {0} bytes")]
+    MalformedCosignature(usize),
+    #[error("Invalid cosignature: {0}")]
+    InvalidCosignature(#[from] SignatureVerificationError),
+    #[error("The input override {input_override} is larger than the start amount {start_amount}")]
+    InvalidCosignerInput {
+        input_override: U256,
+        start_amount: U256,
+    },
+    #[error("The output {index} override {output_override} is smaller than the start amount {start_amount}")]
+    InvalidCosignerOutput {
+        index: usize,
+        output_override: U256,
+        start_amount: U256,
+    },
+    #[error("{overrides} output overrides for {outputs} outputs")]
+    OutputOverridesMismatch { outputs: usize, overrides: usize },
+    #[error("The deadline is before the decay end time")]
+    DeadlineBeforeEndTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{address, Address, Bytes, U256};
+
+    use crate::types::{
+        dutch_order::{CosignerData, DutchOrderV2, OrderInfo},
+        DutchInput, DutchOutput, SignatureVerificationError,
+    };
+
+    use super::{validate_overrides, CosignatureError};
+
+    // the address of the private key `0x00..01`, which signed the cosigner digest on mainnet
+    const COSIGNER: Address = address!("7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+    const COSIGNATURE: &str = "0x9b1c0191b7726645fcfa4ad8631e834f611fe2908519406d96e9d9e433fa415e1f180a8b5f4cd58e2f081582ca27f65f4df1b108d3236aaa9203226bbc8255001c";
+
+    fn order_v2() -> DutchOrderV2 {
+        DutchOrderV2 {
+            info: OrderInfo {
+                reactor: address!("00000011F84B9aa48e5f8aA8B9897600006289Be"),
+                swapper: address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"),
+                nonce: U256::from(1),
+                deadline: U256::from(1000),
+                additionalValidationContract: Address::ZERO,
+                additionalValidationData: Bytes::new(),
+            },
+            cosigner: COSIGNER,
+            input: DutchInput {
+                token: address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+                startAmount: U256::from(1000),
+                endAmount: U256::from(1000),
+            },
+            outputs: vec![DutchOutput {
+                token: address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+                startAmount: U256::from(2000),
+                endAmount: U256::from(1900),
+                recipient: address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"),
+            }],
+            cosignerData: CosignerData {
+                decayStartTime: U256::from(100),
+                decayEndTime: U256::from(200),
+                exclusiveFiller: Address::ZERO,
+                exclusivityOverrideBps: U256::ZERO,
+                inputOverride: U256::ZERO,
+                outputOverrides: vec![U256::from(2100)],
+            },
+            cosignature: COSIGNATURE.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_verify_cosignature() {
+        order_v2().verify_cosignature(1).unwrap();
+    }
+
+    #[test]
+    fn test_verify_cosignature_on_wrong_chain() {
+        let err = order_v2().verify_cosignature(42161).unwrap_err();
+
+        assert!(matches!(
+            err,
+            CosignatureError::InvalidCosignature(SignatureVerificationError::InvalidSigner {
+                expected: COSIGNER,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_tampered_cosigner_data() {
+        let mut order = order_v2();
+        order.cosignerData.outputOverrides = vec![U256::from(2200)];
+
+        assert!(matches!(
+            order.verify_cosignature(1).unwrap_err(),
+            CosignatureError::InvalidCosignature(_)
+        ));
+    }
+
+    #[test]
+    fn test_malformed_cosignature() {
+        let mut order = order_v2();
+        order.cosignature = Bytes::new();
+
+        assert!(matches!(
+            order.verify_cosignature(1).unwrap_err(),
+            CosignatureError::MalformedCosignature(0)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_overrides() {
+        assert!(matches!(
+            validate_overrides(
+                U256::from(1000),
+                U256::from(1001),
+                [U256::from(2000)].into_iter(),
+                &[U256::ZERO]
+            ),
+            Err(CosignatureError::InvalidCosignerInput { .. })
+        ));
+        assert!(matches!(
+            validate_overrides(
+                U256::from(1000),
+                U256::ZERO,
+                [U256::from(2000)].into_iter(),
+                &[U256::from(1999)]
+            ),
+            Err(CosignatureError::InvalidCosignerOutput { index: 0, .. })
+        ));
+        assert!(matches!(
+            validate_overrides(
+                U256::from(1000),
+                U256::ZERO,
+                [U256::from(2000)].into_iter(),
+                &[]
+            ),
+            Err(CosignatureError::OutputOverridesMismatch {
+                outputs: 1,
+                overrides: 0
+            })
+        ));
+    }
+}
@@ -0,0 +1,403 @@
+use std::{fmt::Display, str::FromStr};
+
+use alloy::primitives::{Address, B256};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use super::{Order, OrderStatus, OrdersRequest};
+
+/// The pagination cursor of the orders API: the base64 encoded key of the last order of a
+/// page, in the index the API queried.
+///
+/// ```text
+/// {"chainId_orderStatus":"1_filled","createdAt":1685115350,"orderHash":"0x8b98...6654"}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub index: CursorIndex,
+    pub created_at: u64,
+    pub order_hash: B256,
+}
+
+/// The index the API picks for a combination of filters, along with the indexed values of the
+/// last order. Swappers are called offerers by the API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CursorIndex {
+    ChainId(u64),
+    OrderStatus(OrderStatus),
+    Swapper(Address),
+    Filler(Address),
+    ChainIdOrderStatus(u64, OrderStatus),
+    ChainIdFiller(u64, Address),
+    ChainIdOrderStatusFiller(u64, OrderStatus, Address),
+    SwapperOrderStatus(Address, OrderStatus),
+    FillerOrderStatus(Address, OrderStatus),
+    FillerSwapper(Address, Address),
+    FillerSwapperOrderStatus(Address, Address, OrderStatus),
+}
+
+impl Cursor {
+    /// Builds the cursor pointing after the given order, for the filters of the request.
+    pub fn from_order(request: &OrdersRequest, order: &Order) -> Result<Self, CursorError> {
+        Ok(Self {
+            index: CursorIndex::for_request(request)?,
+            created_at: order.created_at,
+            order_hash: order.order_hash,
+        })
+    }
+
+    /// Encodes the cursor the way the API does, with the index key first.
+    pub fn encode(&self) -> String {
+        let key = format!(
+            r#"{{"{}":{},"createdAt":{},"orderHash":"{}"}}"#,
+            self.index.key(),
+            Value::String(self.index.value()),
+            self.created_at,
+            self.order_hash
+        );
+
+        STANDARD.encode(key)
+    }
+
+    pub fn decode(encoded: &str) -> Result<Self, CursorError> {
+        let mut key = serde_json::from_slice::<Map<String, Value>>(&STANDARD.decode(encoded)?)?;
+
+        let created_at = key
+            .remove("createdAt")
+            .and_then(|created_at| created_at.as_u64())
+            .ok_or(CursorError::MissingField("createdAt"))?;
+
+        let order_hash = key
+            .remove("orderHash")
+            .and_then(|order_hash| order_hash.as_str()?.parse().ok())
+            .ok_or(CursorError::MissingField("orderHash"))?;
+
+        // the index key is the only one left
+        let mut index = key.into_iter();
+        let (Some((index_key, index_value)), None) = (index.next(), index.next()) else {
+            return Err(CursorError::MissingField("index key"));
+        };
+
+        Ok(Self {
+            index: CursorIndex::parse(&index_key, &index_value)?,
+            created_at,
+            order_hash,
+        })
+    }
+}
+
+impl Display for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = CursorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode(s)
+    }
+}
+
+impl CursorIndex {
+    /// Picks the index the API queries for the filters of the request.
+    pub fn for_request(request: &OrdersRequest) -> Result<Self, CursorError> {
+        let index = match (
+            request.chain_id,
+            request.order_status,
+            request.swapper,
+            request.filler,
+        ) {
+            (Some(chain_id), None, None, None) => Self::ChainId(chain_id),
+            (None, Some(status), None, None) => Self::OrderStatus(status),
+            (None, None, Some(swapper), None) => Self::Swapper(swapper),
+            (None, None, None, Some(filler)) => Self::Filler(filler),
+            (Some(chain_id), Some(status), None, None) => {
+                Self::ChainIdOrderStatus(chain_id, status)
+            }
+            (Some(chain_id), None, None, Some(filler)) => Self::ChainIdFiller(chain_id, filler),
+            (Some(chain_id), Some(status), None, Some(filler)) => {
+                Self::ChainIdOrderStatusFiller(chain_id, status, filler)
+            }
+            (None, Some(status), Some(swapper), None) => Self::SwapperOrderStatus(swapper, status),
+            (None, Some(status), None, Some(filler)) => Self::FillerOrderStatus(filler, status),
+            (None, None, Some(swapper), Some(filler)) => Self::FillerSwapper(filler, swapper),
+            (None, Some(status), Some(swapper), Some(filler)) => {
+                Self::FillerSwapperOrderStatus(filler, swapper, status)
+            }
+            _ => return Err(CursorError::UnsupportedFilters),
+        };
+
+        Ok(index)
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::ChainId(_) => "chainId",
+            Self::OrderStatus(_) => "orderStatus",
+            Self::Swapper(_) => "offerer",
+            Self::Filler(_) => "filler",
+            Self::ChainIdOrderStatus(..) => "chainId_orderStatus",
+            Self::ChainIdFiller(..) => "chainId_filler",
+            Self::ChainIdOrderStatusFiller(..) => "chainId_orderStatus_filler",
+            Self::SwapperOrderStatus(..) => "offerer_orderStatus",
+            Self::FillerOrderStatus(..) => "filler_orderStatus",
+            Self::FillerSwapper(..) => "filler_offerer",
+            Self::FillerSwapperOrderStatus(..) => "filler_offerer_orderStatus",
+        }
+    }
+
+    /// Returns the indexed value, its parts joined with underscores. The API indexes every
+    /// value as a string, chain ids alone included.
+    pub fn value(&self) -> String {
+        let parts = match self {
+            Self::ChainId(chain_id) => vec![chain_id.to_string()],
+            Self::OrderStatus(status) => vec![status.to_string()],
+            Self::Swapper(swapper) => vec![address(swapper)],
+            Self::Filler(filler) => vec![address(filler)],
+            Self::ChainIdOrderStatus(chain_id, status) => {
+                vec![chain_id.to_string(), status.to_string()]
+            }
+            Self::ChainIdFiller(chain_id, filler) => vec![chain_id.to_string(), address(filler)],
+            Self::ChainIdOrderStatusFiller(chain_id, status, filler) => {
+                vec![chain_id.to_string(), status.to_string(), address(filler)]
+            }
+            Self::SwapperOrderStatus(swapper, status) => {
+                vec![address(swapper), status.to_string()]
+            }
+            Self::FillerOrderStatus(filler, status) => vec![address(filler), status.to_string()],
+            Self::FillerSwapper(filler, swapper) => vec![address(filler), address(swapper)],
+            Self::FillerSwapperOrderStatus(filler, swapper, status) => {
+                vec![address(filler), address(swapper), status.to_string()]
+            }
+        };
+
+        parts.join("_")
+    }
+
+    fn parse(key: &str, value: &Value) -> Result<Self, CursorError> {
+        let invalid = || CursorError::InvalidIndexValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+
+        let value = match value {
+            Value::String(value) => value.clone(),
+            Value::Number(value) => value.to_string(),
+            _ => return Err(invalid()),
+        };
+        let parts = value.split('_').collect::<Vec<_>>();
+
+        let chain_id = |part: &str| part.parse::<u64>().map_err(|_| invalid());
+        let status =
+            |part: &str| serde_json::from_value::<OrderStatus>(part.into()).map_err(|_| invalid());
+        let address = |part: &str| part.parse::<Address>().map_err(|_| invalid());
+
+        let index = match (key, parts.as_slice()) {
+            ("chainId", [c]) => Self::ChainId(chain_id(c)?),
+            ("orderStatus", [s]) => Self::OrderStatus(status(s)?),
+            ("offerer", [o]) => Self::Swapper(address(o)?),
+            ("filler", [f]) => Self::Filler(address(f)?),
+            ("chainId_orderStatus", [c, s]) => Self::ChainIdOrderStatus(chain_id(c)?, status(s)?),
+            ("chainId_filler", [c, f]) => Self::ChainIdFiller(chain_id(c)?, address(f)?),
+            ("chainId_orderStatus_filler", [c, s, f]) => {
+                Self::ChainIdOrderStatusFiller(chain_id(c)?, status(s)?, address(f)?)
+            }
+            ("offerer_orderStatus", [o, s]) => Self::SwapperOrderStatus(address(o)?, status(s)?),
+            ("filler_orderStatus", [f, s]) => Self::FillerOrderStatus(address(f)?, status(s)?),
+            ("filler_offerer", [f, o]) => Self::FillerSwapper(address(f)?, address(o)?),
+            ("filler_offerer_orderStatus", [f, o, s]) => {
+                Self::FillerSwapperOrderStatus(address(f)?, address(o)?, status(s)?)
+            }
+            ("chainId" | "orderStatus" | "offerer" | "filler", _)
+            | ("chainId_orderStatus" | "chainId_filler" | "chainId_orderStatus_filler", _)
+            | ("offerer_orderStatus" | "filler_orderStatus" | "filler_offerer", _)
+            | ("filler_offerer_orderStatus", _) => return Err(invalid()),
+            _ => return Err(CursorError::UnknownIndex(key.to_string())),
+        };
+
+        Ok(index)
+    }
+}
+
+/// The API indexes addresses in lowercase.
+fn address(address: &Address) -> String {
+    format!("{address:#x}")
+}
+
+#[derive(Error, Debug)]
+pub enum CursorError {
+    #[error("No index of the API supports this combination of filters")]
+    UnsupportedFilters,
+    #[error("Invalid base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("Invalid cursor JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("The cursor has no {0}")]
+    MissingField(&'static str),
+    #[error("Unknown index {0}")]
+    UnknownIndex(String),
+    #[error("Invalid {key} value {value}")]
+    InvalidIndexValue { key: String, value: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{address, b256, Address};
+
+    use crate::types::{Order, OrderStatus, OrdersRequest};
+
+    use super::{Cursor, CursorError, CursorIndex};
+
+    const SWAPPER: Address = address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955");
+    const FILLER: Address = address!("ff8ba4d1fc3762f6154cc942ccf30049a2a0cec6");
+
+    fn order() -> Order {
+        Order {
+            order_hash: b256!("8b984116a793011c9288f00ce0e3a5eb5bee9234e006de154551bc915d676654"),
+            created_at: 1685115350,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        let request = OrdersRequest {
+            chain_id: Some(1),
+            order_status: Some(OrderStatus::Filled),
+            ..Default::default()
+        };
+
+        let cursor = Cursor::from_order(&request, &order()).unwrap();
+
+        assert_eq!(
+            cursor.encode(),
+            "eyJjaGFpbklkX29yZGVyU3RhdHVzIjoiMV9maWxsZWQiLCJjcmVhdGVkQXQiOjE2ODUxMTUzNTAsIm9yZGVySGFzaCI6IjB4OGI5ODQxMTZhNzkzMDExYzkyODhmMDBjZTBlM2E1ZWI1YmVlOTIzNGUwMDZkZTE1NDU1MWJjOTE1ZDY3NjY1NCJ9"
+        );
+    }
+
+    #[test]
+    fn test_single_key_indexes() {
+        // the API indexes chain ids as strings, like every other value
+        let cursors = [
+            (
+                CursorIndex::ChainId(1),
+                "eyJjaGFpbklkIjoiMSIsImNyZWF0ZWRBdCI6MTY4NTExNTM1MCwib3JkZXJIYXNoIjoiMHg4Yjk4NDExNmE3OTMwMTFjOTI4OGYwMGNlMGUzYTVlYjViZWU5MjM0ZTAwNmRlMTU0NTUxYmM5MTVkNjc2NjU0In0=",
+            ),
+            (
+                CursorIndex::Swapper(SWAPPER),
+                "eyJvZmZlcmVyIjoiMHhiOGJmZjY1YjJlZWI2MGQ2YjM3MzEyY2EwNzQwYTc0MmQ1ZTdmOTU1IiwiY3JlYXRlZEF0IjoxNjg1MTE1MzUwLCJvcmRlckhhc2giOiIweDhiOTg0MTE2YTc5MzAxMWM5Mjg4ZjAwY2UwZTNhNWViNWJlZTkyMzRlMDA2ZGUxNTQ1NTFiYzkxNWQ2NzY2NTQifQ==",
+            ),
+            (
+                CursorIndex::Filler(FILLER),
+                "eyJmaWxsZXIiOiIweGZmOGJhNGQxZmMzNzYyZjYxNTRjYzk0MmNjZjMwMDQ5YTJhMGNlYzYiLCJjcmVhdGVkQXQiOjE2ODUxMTUzNTAsIm9yZGVySGFzaCI6IjB4OGI5ODQxMTZhNzkzMDExYzkyODhmMDBjZTBlM2E1ZWI1YmVlOTIzNGUwMDZkZTE1NDU1MWJjOTE1ZDY3NjY1NCJ9",
+            ),
+        ];
+
+        for (index, encoded) in cursors {
+            let cursor = Cursor {
+                index,
+                created_at: order().created_at,
+                order_hash: order().order_hash,
+            };
+
+            assert_eq!(cursor.encode(), encoded);
+            assert_eq!(encoded.parse::<Cursor>().unwrap(), cursor);
+        }
+
+        // numeric chain ids are still accepted
+        let numeric = base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            r#"{"chainId":1,"createdAt":1685115350,"orderHash":"0x8b984116a793011c9288f00ce0e3a5eb5bee9234e006de154551bc915d676654"}"#,
+        );
+        assert_eq!(
+            numeric.parse::<Cursor>().unwrap().index,
+            CursorIndex::ChainId(1)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let requests = [
+            OrdersRequest {
+                chain_id: Some(1),
+                ..Default::default()
+            },
+            OrdersRequest {
+                swapper: Some(SWAPPER),
+                order_status: Some(OrderStatus::InsufficientFunds),
+                ..Default::default()
+            },
+            OrdersRequest {
+                chain_id: Some(42161),
+                order_status: Some(OrderStatus::Open),
+                filler: Some(FILLER),
+                ..Default::default()
+            },
+            OrdersRequest {
+                swapper: Some(SWAPPER),
+                filler: Some(FILLER),
+                order_status: Some(OrderStatus::Filled),
+                ..Default::default()
+            },
+        ];
+
+        for request in requests {
+            let cursor = Cursor::from_order(&request, &order()).unwrap();
+
+            assert_eq!(cursor.encode().parse::<Cursor>().unwrap(), cursor);
+        }
+    }
+
+    #[test]
+    fn test_swapper_index() {
+        let request = OrdersRequest {
+            swapper: Some(SWAPPER),
+            order_status: Some(OrderStatus::Open),
+            ..Default::default()
+        };
+
+        let index = CursorIndex::for_request(&request).unwrap();
+
+        assert_eq!(index.key(), "offerer_orderStatus");
+        assert_eq!(
+            index.value(),
+            "0xb8bff65b2eeb60d6b37312ca0740a742d5e7f955_open"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_filters() {
+        let request = OrdersRequest {
+            chain_id: Some(1),
+            swapper: Some(SWAPPER),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            Cursor::from_order(&request, &order()),
+            Err(CursorError::UnsupportedFilters)
+        ));
+        assert!(matches!(
+            Cursor::from_order(&OrdersRequest::default(), &order()),
+            Err(CursorError::UnsupportedFilters)
+        ));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(matches!(
+            "not base64!".parse::<Cursor>(),
+            Err(CursorError::Base64(_))
+        ));
+        assert!(matches!(
+            Cursor::decode(&base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                r#"{"unknown":"1","createdAt":1,"orderHash":"0x8b984116a793011c9288f00ce0e3a5eb5bee9234e006de154551bc915d676654"}"#
+            )),
+            Err(CursorError::UnknownIndex(_))
+        ));
+    }
+}
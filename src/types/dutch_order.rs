@@ -1,9 +1,15 @@
 use alloy::{
-    primitives::{Address, Bytes, U256},
+    primitives::{Address, Bytes, I256, U256},
     sol,
     sol_types::SolValue,
 };
-use anyhow::Result;
+use thiserror::Error;
+
+use super::{
+    cosignature::{apply_overrides, CosignatureError},
+    math::{bounded_add, bounded_sub, mul_div_down, mul_div_up},
+    OrderDecodeError,
+};
 
 sol! {
     #[derive(Debug)]
@@ -61,26 +67,80 @@ sol! {
         CosignerData cosignerData;
         bytes cosignature;
     }
+
+    #[derive(Debug)]
+    struct NonlinearDutchDecay {
+        uint256 relativeBlocks;
+        int256[] relativeAmounts;
+    }
+
+    #[derive(Debug)]
+    struct DutchInputV3 {
+        address token;
+        uint256 startAmount;
+        NonlinearDutchDecay curve;
+        uint256 maxAmount;
+        uint256 adjustmentPerGweiBaseFee;
+    }
+
+    #[derive(Debug)]
+    struct DutchOutputV3 {
+        address token;
+        uint256 startAmount;
+        NonlinearDutchDecay curve;
+        address recipient;
+        uint256 minAmount;
+        uint256 adjustmentPerGweiBaseFee;
+    }
+
+    #[derive(Debug)]
+    struct CosignerDataV3 {
+        uint256 decayStartBlock;
+        address exclusiveFiller;
+        uint256 exclusivityOverrideBps;
+        uint256 inputOverride;
+        uint256[] outputOverrides;
+    }
+
+    #[derive(Debug)]
+    struct DutchOrderV3 {
+        OrderInfo info;
+        address cosigner;
+        uint256 startingBaseFee;
+        DutchInputV3 input;
+        DutchOutputV3[] outputs;
+        CosignerDataV3 cosignerData;
+        bytes cosignature;
+    }
 }
 
+const BPS: u64 = 10000;
+const GWEI: u64 = 1_000_000_000;
+
 pub enum DutchOrder {
     V1(DutchOrderV1),
     V2(DutchOrderV2),
+    V3(DutchOrderV3),
 }
 
 impl DutchOrder {
-    pub fn try_from_v1(encoded: &Bytes) -> Result<Self> {
+    pub fn try_from_v1(encoded: &Bytes) -> Result<Self, OrderDecodeError> {
         Ok(Self::V1(DutchOrderV1::abi_decode(encoded, true)?))
     }
 
-    pub fn try_from_v2(encoded: &Bytes) -> Result<Self> {
+    pub fn try_from_v2(encoded: &Bytes) -> Result<Self, OrderDecodeError> {
         Ok(Self::V2(DutchOrderV2::abi_decode(encoded, true)?))
     }
 
+    pub fn try_from_v3(encoded: &Bytes) -> Result<Self, OrderDecodeError> {
+        Ok(Self::V3(DutchOrderV3::abi_decode(encoded, true)?))
+    }
+
     pub fn deadline(&self) -> U256 {
         match self {
             DutchOrder::V1(order) => order.info.deadline,
             DutchOrder::V2(order) => order.info.deadline,
+            DutchOrder::V3(order) => order.info.deadline,
         }
     }
 
@@ -88,34 +148,51 @@ impl DutchOrder {
         match self {
             DutchOrder::V1(order) => order.input.token,
             DutchOrder::V2(order) => order.input.token,
+            DutchOrder::V3(order) => order.input.token,
         }
     }
 
-    pub fn decay_start_time(&self) -> U256 {
+    /// Returns the time the decay starts at, or `None` for block-based (V3) orders.
+    pub fn decay_start_time(&self) -> Option<U256> {
         match self {
-            DutchOrder::V1(order) => order.decayStartTime,
-            DutchOrder::V2(order) => order.cosignerData.decayStartTime,
+            DutchOrder::V1(order) => Some(order.decayStartTime),
+            DutchOrder::V2(order) => Some(order.cosignerData.decayStartTime),
+            DutchOrder::V3(_) => None,
         }
     }
 
-    pub fn decay_end_time(&self) -> U256 {
+    /// Returns the time the decay ends at, or `None` for block-based (V3) orders.
+    pub fn decay_end_time(&self) -> Option<U256> {
         match self {
-            DutchOrder::V1(order) => order.decayEndTime,
-            DutchOrder::V2(order) => order.cosignerData.decayEndTime,
+            DutchOrder::V1(order) => Some(order.decayEndTime),
+            DutchOrder::V2(order) => Some(order.cosignerData.decayEndTime),
+            DutchOrder::V3(_) => None,
         }
     }
 
-    pub fn input(&self) -> &DutchInput {
+    /// Returns the block the decay starts at, or `None` for time-based (V1 and V2) orders.
+    pub fn decay_start_block(&self) -> Option<U256> {
         match self {
-            DutchOrder::V1(order) => &order.input,
-            DutchOrder::V2(order) => &order.input,
+            DutchOrder::V1(_) | DutchOrder::V2(_) => None,
+            DutchOrder::V3(order) => Some(order.cosignerData.decayStartBlock),
         }
     }
 
-    pub fn outputs(&self) -> &Vec<DutchOutput> {
+    /// Returns the input of time-based (V1 and V2) orders.
+    pub fn input(&self) -> Option<&DutchInput> {
         match self {
-            DutchOrder::V1(order) => &order.outputs,
-            DutchOrder::V2(order) => &order.outputs,
+            DutchOrder::V1(order) => Some(&order.input),
+            DutchOrder::V2(order) => Some(&order.input),
+            DutchOrder::V3(_) => None,
+        }
+    }
+
+    /// Returns the outputs of time-based (V1 and V2) orders.
+    pub fn outputs(&self) -> Option<&Vec<DutchOutput>> {
+        match self {
+            DutchOrder::V1(order) => Some(&order.outputs),
+            DutchOrder::V2(order) => Some(&order.outputs),
+            DutchOrder::V3(_) => None,
         }
     }
 
@@ -123,6 +200,7 @@ impl DutchOrder {
         match self {
             DutchOrder::V1(order) => order.exclusiveFiller,
             DutchOrder::V2(order) => order.cosignerData.exclusiveFiller,
+            DutchOrder::V3(order) => order.cosignerData.exclusiveFiller,
         }
     }
 
@@ -130,62 +208,316 @@ impl DutchOrder {
         match self {
             DutchOrder::V1(order) => order.exclusivityOverrideBps,
             DutchOrder::V2(order) => order.cosignerData.exclusivityOverrideBps,
+            DutchOrder::V3(order) => order.cosignerData.exclusivityOverrideBps,
+        }
+    }
+
+    /// Resolves the order against the given block for the given filler, whatever its version.
+    pub fn resolve_at_block(
+        &self,
+        timestamp: u64,
+        block_number: u64,
+        base_fee: U256,
+        filler: Address,
+    ) -> OrderResolution {
+        match self {
+            DutchOrder::V1(_) | DutchOrder::V2(_) => self.resolve(timestamp, filler),
+            DutchOrder::V3(order) => order.resolve(timestamp, block_number, base_fee, filler),
         }
     }
 
-    pub fn resolve(&self, timestamp: u64) -> OrderResolution {
+    /// Resolves a time-based (V1 or V2) order for the given filler. V3 orders decay per block,
+    /// and must be resolved with [`DutchOrder::resolve_at_block`].
+    pub fn resolve(&self, timestamp: u64, filler: Address) -> OrderResolution {
+        self.try_resolve(timestamp, filler)
+            .unwrap_or_else(OrderResolution::Invalid)
+    }
+
+    fn try_resolve(
+        &self,
+        timestamp: u64,
+        filler: Address,
+    ) -> Result<OrderResolution, InvalidOrderReason> {
+        let (Some(input), Some(outputs), Some(decay_start_time), Some(decay_end_time)) = (
+            self.input(),
+            self.outputs(),
+            self.decay_start_time(),
+            self.decay_end_time(),
+        ) else {
+            return Err(InvalidOrderReason::BlockBasedDecay);
+        };
+
         let timestamp = U256::from(timestamp);
 
         if self.deadline().lt(&timestamp) {
-            return OrderResolution::Expired;
+            return Ok(OrderResolution::Expired);
+        };
+
+        if self.deadline() < decay_end_time {
+            return Err(InvalidOrderReason::DeadlineBeforeEndTime);
+        }
+
+        if decay_end_time < decay_start_time {
+            return Err(InvalidOrderReason::EndTimeBeforeStartTime);
+        }
+
+        // V1 orders can't decay both their input and their outputs
+        if let DutchOrder::V1(order) = self {
+            if order.input.startAmount != order.input.endAmount
+                && order
+                    .outputs
+                    .iter()
+                    .any(|output| output.startAmount != output.endAmount)
+            {
+                return Err(InvalidOrderReason::InputAndOutputDecay);
+            }
+        }
+
+        // apply cosigner overrides
+
+        let input_start_amount = input.startAmount;
+        let output_start_amounts = outputs.iter().map(|output| output.startAmount).collect();
+
+        let (input_start_amount, output_start_amounts) = match self {
+            DutchOrder::V2(order) => apply_overrides(
+                input_start_amount,
+                order.cosignerData.inputOverride,
+                output_start_amounts,
+                &order.cosignerData.outputOverrides,
+            )?,
+            _ => (input_start_amount, output_start_amounts),
         };
 
         // resolve over the decay curve
 
+        if input_start_amount > input.endAmount {
+            return Err(InvalidOrderReason::IncorrectAmounts);
+        }
+
         let input: ResolvedInput = ResolvedInput {
             token: self.input_token(),
             amount: resolve_decay(
                 timestamp,
-                self.decay_start_time(),
-                self.decay_end_time(),
-                self.input().startAmount,
-                self.input().endAmount,
-            ),
+                decay_start_time,
+                decay_end_time,
+                input_start_amount,
+                input.endAmount,
+            )?,
         };
 
-        let outputs = self
-            .outputs()
-            .iter()
-            .map(|output| {
-                let mut amount = resolve_decay(
-                    timestamp,
-                    self.decay_start_time(),
-                    self.decay_end_time(),
-                    output.startAmount,
-                    output.endAmount,
-                );
-
-                // add exclusivity override to amount
-                if self.decay_start_time().gt(&timestamp) && !self.exclusive_filler().is_zero() {
-                    let exclusivity = self
-                        .exclusivity_override_bps()
-                        .wrapping_add(U256::from(10000));
-                    let exclusivity = exclusivity.wrapping_mul(amount);
-                    amount = exclusivity.wrapping_div(U256::from(10000));
-                };
-
-                ResolvedOutput {
-                    token: output.token,
-                    amount,
-                    recipient: output.recipient,
-                }
-            })
-            .collect();
-
-        OrderResolution::Resolved(ResolvedOrder { input, outputs })
+        let mut resolved_outputs = Vec::with_capacity(outputs.len());
+
+        for (output, start_amount) in outputs.iter().zip(output_start_amounts) {
+            if start_amount < output.endAmount {
+                return Err(InvalidOrderReason::IncorrectAmounts);
+            }
+
+            let amount = resolve_decay(
+                timestamp,
+                decay_start_time,
+                decay_end_time,
+                start_amount,
+                output.endAmount,
+            )?;
+
+            resolved_outputs.push(ResolvedOutput {
+                token: output.token,
+                amount,
+                recipient: output.recipient,
+            });
+        }
+
+        // the exclusivity ends when the decay starts
+        apply_exclusivity(
+            ResolvedOrder {
+                input,
+                outputs: resolved_outputs,
+            },
+            filler,
+            self.exclusive_filler(),
+            self.exclusivity_override_bps(),
+            timestamp > decay_start_time,
+        )
+    }
+}
+
+impl DutchOrderV3 {
+    /// Resolves the order the same way the V3 reactor does: cosigner overrides are applied first,
+    /// then amounts are adjusted to the base fee variation, and finally decayed over their curve.
+    pub fn resolve(
+        &self,
+        timestamp: u64,
+        block_number: u64,
+        base_fee: U256,
+        filler: Address,
+    ) -> OrderResolution {
+        self.try_resolve(timestamp, block_number, base_fee, filler)
+            .unwrap_or_else(OrderResolution::Invalid)
+    }
+
+    fn try_resolve(
+        &self,
+        timestamp: u64,
+        block_number: u64,
+        base_fee: U256,
+        filler: Address,
+    ) -> Result<OrderResolution, InvalidOrderReason> {
+        if self.info.deadline.lt(&U256::from(timestamp)) {
+            return Ok(OrderResolution::Expired);
+        };
+
+        let block_number = U256::from(block_number);
+        let decay_start_block = self.cosignerData.decayStartBlock;
+
+        // apply cosigner overrides
+
+        let (mut input_start_amount, mut output_start_amounts) = apply_overrides(
+            self.input.startAmount,
+            self.cosignerData.inputOverride,
+            self.outputs
+                .iter()
+                .map(|output| output.startAmount)
+                .collect(),
+            &self.cosignerData.outputOverrides,
+        )?;
+
+        // adjust amounts to the base fee variation
+
+        let (Ok(base_fee), Ok(starting_base_fee)) = (
+            I256::try_from(base_fee),
+            I256::try_from(self.startingBaseFee),
+        ) else {
+            return Err(InvalidOrderReason::Overflow);
+        };
+
+        let gas_delta = base_fee
+            .checked_sub(starting_base_fee)
+            .ok_or(InvalidOrderReason::Overflow)?;
+
+        if !self.input.adjustmentPerGweiBaseFee.is_zero() {
+            let input_delta = gas_adjustment(self.input.adjustmentPerGweiBaseFee, gas_delta)?;
+
+            // a base fee increase increases the input
+            input_start_amount = bounded_add(
+                input_start_amount,
+                input_delta,
+                U256::ZERO,
+                self.input.maxAmount,
+            );
+        }
+
+        for (output, start_amount) in self.outputs.iter().zip(output_start_amounts.iter_mut()) {
+            if output.adjustmentPerGweiBaseFee.is_zero() {
+                continue;
+            }
+
+            let output_delta = gas_adjustment(output.adjustmentPerGweiBaseFee, gas_delta)?;
+
+            // a base fee increase decreases the outputs
+            *start_amount = bounded_sub(*start_amount, output_delta, output.minAmount, U256::MAX);
+        }
+
+        // resolve over the decay curves
+
+        let input_amount = self.input.curve.decay(
+            input_start_amount,
+            decay_start_block,
+            block_number,
+            U256::ZERO,
+            self.input.maxAmount,
+            true,
+        )?;
+
+        let mut outputs = Vec::with_capacity(self.outputs.len());
+
+        for (output, start_amount) in self.outputs.iter().zip(output_start_amounts) {
+            let amount = output.curve.decay(
+                start_amount,
+                decay_start_block,
+                block_number,
+                output.minAmount,
+                U256::MAX,
+                false,
+            )?;
+
+            outputs.push(ResolvedOutput {
+                token: output.token,
+                amount,
+                recipient: output.recipient,
+            });
+        }
+
+        // the exclusivity ends at the decay start block
+        apply_exclusivity(
+            ResolvedOrder {
+                input: ResolvedInput {
+                    token: self.input.token,
+                    amount: input_amount,
+                },
+                outputs,
+            },
+            filler,
+            self.cosignerData.exclusiveFiller,
+            self.cosignerData.exclusivityOverrideBps,
+            block_number > decay_start_block,
+        )
     }
 }
 
+/// Applies the exclusivity the same way the reactors' `ExclusivityLib` does: until it ends, any
+/// filler other than the exclusive one pays the override on the outputs, or can't fill at all
+/// when there is no override.
+fn apply_exclusivity(
+    mut order: ResolvedOrder,
+    filler: Address,
+    exclusive_filler: Address,
+    exclusivity_override_bps: U256,
+    exclusivity_ended: bool,
+) -> Result<OrderResolution, InvalidOrderReason> {
+    if exclusive_filler.is_zero() || exclusivity_ended || filler == exclusive_filler {
+        return Ok(OrderResolution::Resolved(order));
+    }
+
+    if exclusivity_override_bps.is_zero() {
+        return Ok(OrderResolution::Exclusive(exclusive_filler));
+    }
+
+    let scaling = exclusivity_override_bps
+        .checked_add(U256::from(BPS))
+        .ok_or(InvalidOrderReason::Overflow)?;
+
+    for output in &mut order.outputs {
+        output.amount = mul_div_up(output.amount, scaling, U256::from(BPS))
+            .ok_or(InvalidOrderReason::Overflow)?;
+    }
+
+    Ok(OrderResolution::Resolved(order))
+}
+
+/// Computes the amount adjustment for a base fee variation, rounding in favor of the swapper.
+fn gas_adjustment(
+    adjustment_per_gwei_base_fee: U256,
+    gas_delta: I256,
+) -> Result<I256, InvalidOrderReason> {
+    let adjustment = if gas_delta.is_negative() {
+        mul_div_up(
+            adjustment_per_gwei_base_fee,
+            gas_delta.unsigned_abs(),
+            U256::from(GWEI),
+        )
+        .and_then(|adjustment| I256::try_from(adjustment).ok()?.checked_neg())
+    } else {
+        mul_div_down(
+            adjustment_per_gwei_base_fee,
+            gas_delta.unsigned_abs(),
+            U256::from(GWEI),
+        )
+        .and_then(|adjustment| I256::try_from(adjustment).ok())
+    };
+
+    adjustment.ok_or(InvalidOrderReason::Overflow)
+}
+
 #[derive(Debug, Clone)]
 pub struct ResolvedInput {
     pub token: Address,
@@ -209,7 +541,35 @@ pub struct ResolvedOrder {
 pub enum OrderResolution {
     Resolved(ResolvedOrder),
     Expired,
-    Invalid,
+    /// The order can't be filled yet at this block.
+    NotFillable,
+    /// The order is exclusive to another filler for now.
+    Exclusive(Address),
+    /// The reactor would revert when filling the order.
+    Invalid(InvalidOrderReason),
+}
+
+/// Why the reactor would refuse to fill an order.
+#[derive(Error, Debug)]
+pub enum InvalidOrderReason {
+    #[error("The amounts overflow")]
+    Overflow,
+    #[error("The deadline is before the decay end time")]
+    DeadlineBeforeEndTime,
+    #[error("The decay end time is before the decay start time")]
+    EndTimeBeforeStartTime,
+    #[error("Both the input and the outputs decay")]
+    InputAndOutputDecay,
+    #[error("The input decays downward or an output decays upward")]
+    IncorrectAmounts,
+    #[error("The decay curve has more than 16 points")]
+    InvalidDecayCurve,
+    #[error("Both the input and the outputs scale with the priority fee")]
+    InputOutputScaled,
+    #[error("The order decays per block, and must be resolved at a block")]
+    BlockBasedDecay,
+    #[error("Invalid cosigner data: {0}")]
+    InvalidCosignerData(#[from] CosignatureError),
 }
 
 fn resolve_decay(
@@ -218,39 +578,439 @@ fn resolve_decay(
     end_time: U256,
     start_amount: U256,
     end_amount: U256,
-) -> U256 {
-    if end_time.le(&at_time) {
-        return end_amount;
+) -> Result<U256, InvalidOrderReason> {
+    if end_time.lt(&start_time) {
+        return Err(InvalidOrderReason::EndTimeBeforeStartTime);
     }
 
-    if at_time.le(&start_time) {
-        return start_amount;
+    if end_time.le(&at_time) {
+        return Ok(end_amount);
     }
 
-    if end_time.eq(&start_time) {
-        return start_amount;
+    if at_time.le(&start_time) {
+        return Ok(start_amount);
     }
 
-    if start_amount.eq(&end_amount) {
-        return start_amount;
-    }
+    // start_time < at_time < end_time, so the duration can't be zero
+    let duration = end_time - start_time;
+    let elapsed = at_time - start_time;
 
-    let duration = end_time.wrapping_sub(start_time);
-    let elapsed = at_time.wrapping_sub(start_time);
-    // TODO: better handle overflows
+    // the reactors' DutchDecayLib rounds in favor of the swapper, for every order version
     if start_amount.gt(&end_amount) {
         // decaying downward
-        let decay = start_amount
-            .wrapping_sub(end_amount)
-            .wrapping_mul(elapsed)
-            .wrapping_div(duration);
-        start_amount.wrapping_sub(decay)
+        let decay = mul_div_down(start_amount - end_amount, elapsed, duration)
+            .ok_or(InvalidOrderReason::Overflow)?;
+        Ok(start_amount - decay)
     } else {
         // decaying upward
-        let decay = end_amount
-            .wrapping_sub(start_amount)
-            .wrapping_mul(elapsed)
-            .wrapping_div(duration);
-        start_amount.wrapping_add(decay)
+        let decay = mul_div_up(end_amount - start_amount, elapsed, duration)
+            .ok_or(InvalidOrderReason::Overflow)?;
+        start_amount
+            .checked_add(decay)
+            .ok_or(InvalidOrderReason::Overflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{address, Address, Bytes, I256, U256},
+        sol_types::SolValue,
+    };
+
+    use crate::types::CosignatureError;
+
+    use super::{
+        CosignerData, CosignerDataV3, DutchInput, DutchInputV3, DutchOrder, DutchOrderV1,
+        DutchOrderV2, DutchOrderV3, DutchOutput, DutchOutputV3, InvalidOrderReason,
+        NonlinearDutchDecay, OrderInfo, OrderResolution, GWEI,
+    };
+
+    const FILLER: Address = address!("1111111111111111111111111111111111111111");
+    const EXCLUSIVE_FILLER: Address = address!("b507d4ef5ed7a01e37cb578f497329cdb3c273a5");
+
+    fn order_v2(input_override: u64, output_override: u64) -> DutchOrderV2 {
+        DutchOrderV2 {
+            info: OrderInfo {
+                reactor: address!("00000011F84B9aa48e5f8aA8B9897600006289Be"),
+                swapper: address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"),
+                nonce: U256::from(1),
+                deadline: U256::from(1000),
+                additionalValidationContract: Address::ZERO,
+                additionalValidationData: Bytes::new(),
+            },
+            cosigner: address!("4449Cd34d1eb1FEDCF02A1Be3834FfDe8E6A6180"),
+            input: DutchInput {
+                token: address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+                startAmount: U256::from(1000),
+                endAmount: U256::from(1000),
+            },
+            outputs: vec![DutchOutput {
+                token: address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+                startAmount: U256::from(2000),
+                endAmount: U256::from(1900),
+                recipient: address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"),
+            }],
+            cosignerData: CosignerData {
+                decayStartTime: U256::from(100),
+                decayEndTime: U256::from(200),
+                exclusiveFiller: Address::ZERO,
+                exclusivityOverrideBps: U256::ZERO,
+                inputOverride: U256::from(input_override),
+                outputOverrides: vec![U256::from(output_override)],
+            },
+            cosignature: Bytes::new(),
+        }
+    }
+
+    fn resolved_amounts(resolution: OrderResolution) -> (U256, U256) {
+        match resolution {
+            OrderResolution::Resolved(resolved) => {
+                (resolved.input.amount, resolved.outputs[0].amount)
+            }
+            resolution => panic!("The order should be resolved, got {resolution:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_v2_without_overrides() {
+        let order = DutchOrder::V2(order_v2(0, 0));
+
+        assert_eq!(
+            resolved_amounts(order.resolve(150, FILLER)),
+            (U256::from(1000), U256::from(1950))
+        );
+    }
+
+    #[test]
+    fn test_resolve_v2_with_overrides() {
+        let order = DutchOrder::V2(order_v2(901, 2100));
+
+        assert_eq!(
+            resolved_amounts(order.resolve(50, FILLER)),
+            (U256::from(901), U256::from(2100))
+        );
+        // the input decays upward from its override, rounding up
+        assert_eq!(
+            resolved_amounts(order.resolve(137, FILLER)),
+            (U256::from(938), U256::from(2026))
+        );
+        assert_eq!(
+            resolved_amounts(order.resolve(250, FILLER)),
+            (U256::from(1000), U256::from(1900))
+        );
+    }
+
+    #[test]
+    fn test_resolve_v2_with_exclusivity() {
+        let mut order = order_v2(0, 2100);
+        order.cosignerData.exclusiveFiller = EXCLUSIVE_FILLER;
+        order.cosignerData.exclusivityOverrideBps = U256::from(100);
+
+        let order = DutchOrder::V2(order);
+
+        // other fillers pay the override up to the decay start time included
+        assert_eq!(
+            resolved_amounts(order.resolve(100, FILLER)),
+            (U256::from(1000), U256::from(2121))
+        );
+        assert_eq!(
+            resolved_amounts(order.resolve(100, EXCLUSIVE_FILLER)),
+            (U256::from(1000), U256::from(2100))
+        );
+        assert_eq!(
+            resolved_amounts(order.resolve(101, FILLER)),
+            (U256::from(1000), U256::from(2098))
+        );
+    }
+
+    #[test]
+    fn test_resolve_v2_with_strict_exclusivity() {
+        let mut order = order_v2(0, 0);
+        order.cosignerData.exclusiveFiller = EXCLUSIVE_FILLER;
+
+        let order = DutchOrder::V2(order);
+
+        assert!(matches!(
+            order.resolve(100, FILLER),
+            OrderResolution::Exclusive(EXCLUSIVE_FILLER)
+        ));
+        assert!(matches!(
+            order.resolve(100, EXCLUSIVE_FILLER),
+            OrderResolution::Resolved(_)
+        ));
+        assert!(matches!(
+            order.resolve(101, FILLER),
+            OrderResolution::Resolved(_)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_v2_with_invalid_overrides() {
+        assert!(matches!(
+            DutchOrder::V2(order_v2(1001, 0)).resolve(150, FILLER),
+            OrderResolution::Invalid(InvalidOrderReason::InvalidCosignerData(
+                CosignatureError::InvalidCosignerInput { .. }
+            ))
+        ));
+        assert!(matches!(
+            DutchOrder::V2(order_v2(0, 1999)).resolve(150, FILLER),
+            OrderResolution::Invalid(InvalidOrderReason::InvalidCosignerData(
+                CosignatureError::InvalidCosignerOutput { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_v2_with_deadline_before_decay_end() {
+        let mut order = order_v2(0, 0);
+        order.info.deadline = U256::from(150);
+
+        assert!(matches!(
+            DutchOrder::V2(order).resolve(120, FILLER),
+            OrderResolution::Invalid(InvalidOrderReason::DeadlineBeforeEndTime)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_v2_with_end_time_before_start_time() {
+        let mut order = order_v2(0, 0);
+        order.cosignerData.decayStartTime = U256::from(300);
+
+        assert!(matches!(
+            DutchOrder::V2(order).resolve(120, FILLER),
+            OrderResolution::Invalid(InvalidOrderReason::EndTimeBeforeStartTime)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_v2_with_output_decaying_upward() {
+        let mut order = order_v2(0, 0);
+        order.outputs[0].endAmount = U256::from(2100);
+
+        assert!(matches!(
+            DutchOrder::V2(order).resolve(120, FILLER),
+            OrderResolution::Invalid(InvalidOrderReason::IncorrectAmounts)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_v2_with_overflowing_exclusivity() {
+        let mut order = order_v2(0, 0);
+        order.cosignerData.exclusiveFiller = EXCLUSIVE_FILLER;
+        order.cosignerData.exclusivityOverrideBps = U256::MAX;
+
+        assert!(matches!(
+            DutchOrder::V2(order).resolve(50, FILLER),
+            OrderResolution::Invalid(InvalidOrderReason::Overflow)
+        ));
+    }
+
+    fn order_v1() -> DutchOrderV1 {
+        let order = order_v2(0, 0);
+
+        DutchOrderV1 {
+            info: order.info,
+            decayStartTime: U256::from(100),
+            decayEndTime: U256::from(200),
+            exclusiveFiller: Address::ZERO,
+            exclusivityOverrideBps: U256::ZERO,
+            input: order.input,
+            outputs: order.outputs,
+        }
+    }
+
+    #[test]
+    fn test_resolve_v1_with_input_and_output_decay() {
+        let mut order = order_v1();
+        order.input.endAmount = U256::from(1100);
+
+        assert!(matches!(
+            DutchOrder::V1(order).resolve(150, FILLER),
+            OrderResolution::Invalid(InvalidOrderReason::InputAndOutputDecay)
+        ));
+    }
+
+    // DutchDecayLib.decay: startAmount + (endAmount - startAmount).mulDivUp(elapsed, duration)
+    #[test]
+    fn test_resolve_v1_rounds_upward_decay_up() {
+        let mut order = order_v1();
+        order.input.endAmount = U256::from(1003);
+        order.outputs[0].endAmount = U256::from(2000);
+
+        let order = DutchOrder::V1(order);
+
+        // 3 * 50 / 100 = 1.5
+        assert_eq!(
+            resolved_amounts(order.resolve(150, FILLER)),
+            (U256::from(1002), U256::from(2000))
+        );
+        // 3 * 33 / 100 = 0.99
+        assert_eq!(
+            resolved_amounts(order.resolve(133, FILLER)),
+            (U256::from(1001), U256::from(2000))
+        );
+    }
+
+    // DutchDecayLib.decay: startAmount - (startAmount - endAmount).mulDivDown(elapsed, duration)
+    #[test]
+    fn test_resolve_v1_rounds_downward_decay_down() {
+        let mut order = order_v1();
+        order.outputs[0].endAmount = U256::from(1997);
+
+        // 3 * 50 / 100 = 1.5
+        assert_eq!(
+            resolved_amounts(DutchOrder::V1(order).resolve(150, FILLER)),
+            (U256::from(1000), U256::from(1999))
+        );
+    }
+
+    // ExclusivityLib: the exclusive filler keeps its rights while
+    // `block.timestamp <= exclusivityEndTime`, others pay `amount.mulDivUp(BPS + bps, BPS)`
+    #[test]
+    fn test_resolve_v1_with_exclusivity() {
+        let mut order = order_v1();
+        order.exclusiveFiller = EXCLUSIVE_FILLER;
+        order.exclusivityOverrideBps = U256::from(100);
+        order.outputs[0].startAmount = U256::from(2001);
+        order.outputs[0].endAmount = U256::from(2001);
+
+        let order = DutchOrder::V1(order);
+
+        // 2001 * 10100 / 10000 = 2021.01
+        assert_eq!(
+            resolved_amounts(order.resolve(100, FILLER)),
+            (U256::from(1000), U256::from(2022))
+        );
+        assert_eq!(
+            resolved_amounts(order.resolve(100, EXCLUSIVE_FILLER)),
+            (U256::from(1000), U256::from(2001))
+        );
+        assert_eq!(
+            resolved_amounts(order.resolve(101, FILLER)),
+            (U256::from(1000), U256::from(2001))
+        );
+    }
+
+    fn order_v3(output_adjustment: u64) -> DutchOrderV3 {
+        DutchOrderV3 {
+            info: OrderInfo {
+                reactor: address!("B274d5F4b833b61B340b654d600A864fB604a87c"),
+                swapper: address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"),
+                nonce: U256::from(1),
+                deadline: U256::from(1000),
+                additionalValidationContract: Address::ZERO,
+                additionalValidationData: Bytes::new(),
+            },
+            cosigner: Address::ZERO,
+            startingBaseFee: U256::from(GWEI),
+            input: DutchInputV3 {
+                token: address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+                startAmount: U256::from(1000),
+                curve: NonlinearDutchDecay {
+                    relativeBlocks: U256::ZERO,
+                    relativeAmounts: vec![],
+                },
+                maxAmount: U256::from(1000),
+                adjustmentPerGweiBaseFee: U256::ZERO,
+            },
+            outputs: vec![DutchOutputV3 {
+                token: address!("2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599"),
+                startAmount: U256::from(2000),
+                curve: NonlinearDutchDecay {
+                    relativeBlocks: U256::from(10),
+                    relativeAmounts: vec![I256::try_from(1000).unwrap()],
+                },
+                recipient: address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"),
+                minAmount: U256::from(1000),
+                adjustmentPerGweiBaseFee: U256::from(output_adjustment),
+            }],
+            cosignerData: CosignerDataV3 {
+                decayStartBlock: U256::from(100),
+                exclusiveFiller: Address::ZERO,
+                exclusivityOverrideBps: U256::ZERO,
+                inputOverride: U256::ZERO,
+                outputOverrides: vec![U256::ZERO],
+            },
+            cosignature: Bytes::new(),
+        }
+    }
+
+    fn resolved_output(resolution: OrderResolution) -> U256 {
+        match resolution {
+            OrderResolution::Resolved(resolved) => resolved.outputs[0].amount,
+            resolution => panic!("The order should be resolved, got {resolution:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_v3() {
+        let encoded = Bytes::from(order_v3(0).abi_encode());
+
+        let order = DutchOrder::try_from_v3(&encoded).unwrap();
+
+        assert_eq!(order.decay_start_block(), Some(U256::from(100)));
+        assert_eq!(order.decay_start_time(), None);
+    }
+
+    #[test]
+    fn test_resolve_v3_over_curve() {
+        let order = DutchOrder::V3(order_v3(0));
+
+        let base_fee = U256::from(GWEI);
+
+        assert_eq!(
+            resolved_output(order.resolve_at_block(0, 100, base_fee, FILLER)),
+            U256::from(2000)
+        );
+        assert_eq!(
+            resolved_output(order.resolve_at_block(0, 105, base_fee, FILLER)),
+            U256::from(1500)
+        );
+        assert_eq!(
+            resolved_output(order.resolve_at_block(0, 200, base_fee, FILLER)),
+            U256::from(1000)
+        );
+    }
+
+    #[test]
+    fn test_resolve_v3_with_base_fee_adjustment() {
+        let order = order_v3(100);
+
+        let resolution = order.resolve(0, 105, U256::from(3 * GWEI), FILLER);
+
+        assert_eq!(resolved_output(resolution), U256::from(1300));
+    }
+
+    #[test]
+    fn test_resolve_v3_with_block_exclusivity() {
+        let mut order = order_v3(0);
+        order.cosignerData.exclusiveFiller = EXCLUSIVE_FILLER;
+        order.cosignerData.exclusivityOverrideBps = U256::from(100);
+
+        let base_fee = U256::from(GWEI);
+
+        assert_eq!(
+            resolved_output(order.resolve(0, 100, base_fee, FILLER)),
+            U256::from(2020)
+        );
+        assert_eq!(
+            resolved_output(order.resolve(0, 100, base_fee, EXCLUSIVE_FILLER)),
+            U256::from(2000)
+        );
+        assert_eq!(
+            resolved_output(order.resolve(0, 101, base_fee, FILLER)),
+            U256::from(1900)
+        );
+    }
+
+    #[test]
+    fn test_resolve_v3_expired() {
+        let order = order_v3(0);
+
+        assert!(matches!(
+            order.resolve(1001, 105, U256::from(GWEI), FILLER),
+            OrderResolution::Expired
+        ));
     }
 }
@@ -1,17 +1,35 @@
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
 
 use alloy::primitives::{Address, B256};
 
-#[derive(Debug, Clone)]
+use super::{OrderType, SettledAmount};
+
+#[derive(Debug, Clone, Default)]
 pub struct FillEvent {
     pub order_hash: B256,
     pub filler: Address,
     pub swapper: Address,
+    /// The reactor which emitted the `Fill` event.
+    pub reactor: Address,
+    /// The type of orders settled by the reactor, if it is a known one. Reactors settling
+    /// several types, such as the mainnet Dutch and limit reactor, leave it unknown.
+    pub order_type: Option<OrderType>,
     pub tx: B256,
     pub block_number: u64,
+    pub block_hash: B256,
+    /// The index of the `Fill` log in its block. Along with `block_hash`, it uniquely identifies
+    /// the fill.
+    pub log_index: u64,
+    /// The amounts the fill settled, once decoded from the transaction receipt.
+    pub settled_amounts: Option<Vec<SettledAmount>>,
 }
 
 impl FillEvent {
+    /// Creates a fill of an unknown reactor, in a block identified by its number only. The other
+    /// fields are set with [`FillEvent::with_reactor`] and [`FillEvent::with_log`].
     pub fn new(
         order_hash: B256,
         filler: Address,
@@ -25,12 +43,48 @@ impl FillEvent {
             swapper,
             tx,
             block_number,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_reactor(mut self, reactor: Address, order_type: Option<OrderType>) -> Self {
+        self.reactor = reactor;
+        self.order_type = order_type;
+        self
+    }
+
+    pub fn with_log(mut self, block_hash: B256, log_index: u64) -> Self {
+        self.block_hash = block_hash;
+        self.log_index = log_index;
+        self
+    }
+
+    /// Identifies the `Fill` log, in block order. Unlike the order hash, it tells apart the fills
+    /// of the same order on both sides of a reorg.
+    pub fn log_key(&self) -> (u64, u64, B256) {
+        (self.block_number, self.log_index, self.block_hash)
+    }
+}
+
+/// A fill seen by a live stream. Fills can be reorged out after being streamed, in which case
+/// they're streamed again as `Reverted` and should be undone.
+#[derive(Debug, Clone)]
+pub enum FillNotification {
+    Confirmed(FillEvent),
+    Reverted(FillEvent),
+}
+
+impl FillNotification {
+    pub fn fill(&self) -> &FillEvent {
+        match self {
+            FillNotification::Confirmed(fill) | FillNotification::Reverted(fill) => fill,
         }
     }
 }
 
 impl Eq for FillEvent {}
 
+/// Fills of the same order are equal, see [`FillEvent::log_key`] to tell their logs apart.
 impl PartialEq for FillEvent {
     fn eq(&self, other: &Self) -> bool {
         self.order_hash == other.order_hash
@@ -43,8 +97,72 @@ impl PartialOrd for FillEvent {
     }
 }
 
+/// Fills are ordered by block, then by log index. The ordering doesn't agree with equality,
+/// [`FillByLog`] orders and compares fills consistently by their log.
 impl Ord for FillEvent {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.block_number.cmp(&other.block_number)
+        self.block_number
+            .cmp(&other.block_number)
+            .then(self.log_index.cmp(&other.log_index))
+    }
+}
+
+/// A fill compared, ordered and hashed by its [`FillEvent::log_key`].
+#[derive(Debug, Clone)]
+pub struct FillByLog(pub FillEvent);
+
+impl Eq for FillByLog {}
+
+impl PartialEq for FillByLog {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.log_key() == other.0.log_key()
+    }
+}
+
+impl PartialOrd for FillByLog {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FillByLog {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.log_key().cmp(&other.0.log_key())
+    }
+}
+
+impl Hash for FillByLog {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.log_key().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, B256};
+
+    use super::{FillByLog, FillEvent};
+
+    fn fill(block_number: u64, log_index: u64) -> FillEvent {
+        FillEvent::new(
+            B256::repeat_byte(1),
+            Address::ZERO,
+            Address::ZERO,
+            B256::ZERO,
+            block_number,
+        )
+        .with_log(B256::with_last_byte(block_number as u8), log_index)
+    }
+
+    #[test]
+    fn test_fills_by_log() {
+        // the same order can be filled by several logs, e.g. on both sides of a reorg
+        let reorged = fill(100, 1).with_log(B256::repeat_byte(0xff), 1);
+
+        assert_eq!(fill(100, 1), reorged);
+        assert_eq!(FillByLog(fill(100, 1)), FillByLog(fill(100, 1)));
+        assert_ne!(FillByLog(fill(100, 1)), FillByLog(reorged));
+        assert!(FillByLog(fill(100, 1)) < FillByLog(fill(100, 2)));
+        assert!(FillByLog(fill(100, 2)) < FillByLog(fill(101, 0)));
     }
 }
@@ -0,0 +1,50 @@
+use alloy::primitives::{I256, U256};
+
+/// Computes `x * y / denominator` rounded down, returning `None` where solmate's
+/// `mulDivDown` would revert.
+pub(crate) fn mul_div_down(x: U256, y: U256, denominator: U256) -> Option<U256> {
+    x.checked_mul(y)?.checked_div(denominator)
+}
+
+/// Computes `x * y / denominator` rounded up, returning `None` where solmate's
+/// `mulDivUp` would revert.
+pub(crate) fn mul_div_up(x: U256, y: U256, denominator: U256) -> Option<U256> {
+    let product = x.checked_mul(y)?;
+
+    if denominator.is_zero() {
+        return None;
+    }
+
+    if product.is_zero() {
+        return Some(U256::ZERO);
+    }
+
+    Some((product - U256::from(1)) / denominator + U256::from(1))
+}
+
+/// Clamps `value` between `min` and `max`.
+pub(crate) fn bound(value: U256, min: U256, max: U256) -> U256 {
+    value.max(min).min(max)
+}
+
+/// Subtracts a signed amount from `a`, then clamps the result between `min` and `max`.
+pub(crate) fn bounded_sub(a: U256, b: I256, min: U256, max: U256) -> U256 {
+    let result = if b.is_negative() {
+        a.saturating_add(b.unsigned_abs())
+    } else {
+        a.saturating_sub(b.unsigned_abs())
+    };
+
+    bound(result, min, max)
+}
+
+/// Adds a signed amount to `a`, then clamps the result between `min` and `max`.
+pub(crate) fn bounded_add(a: U256, b: I256, min: U256, max: U256) -> U256 {
+    let result = if b.is_negative() {
+        a.saturating_sub(b.unsigned_abs())
+    } else {
+        a.saturating_add(b.unsigned_abs())
+    };
+
+    bound(result, min, max)
+}
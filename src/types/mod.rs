@@ -1,6 +1,11 @@
+mod cosignature;
+mod cursor;
 mod dutch_order;
 mod fill_event;
+mod math;
+mod nonlinear_dutch_decay;
 mod order;
+mod order_hash;
 mod order_input;
 mod order_notification;
 mod order_output;
@@ -8,16 +13,26 @@ mod order_response;
 mod order_status;
 mod order_type;
 mod orders_request;
+mod permit2;
+mod priority_order;
 mod settled_amount;
 
-pub use dutch_order::{DutchOrder, DutchInput, DutchOutput, OrderResolution};
-pub use fill_event::FillEvent;
-pub use order::Order;
+pub use cosignature::CosignatureError;
+pub use cursor::{Cursor, CursorError, CursorIndex};
+pub use dutch_order::{
+    CosignerData, CosignerDataV3, DutchInput, DutchInputV3, DutchOrder, DutchOrderV1, DutchOrderV2,
+    DutchOrderV3, DutchOutput, DutchOutputV3, InvalidOrderReason, NonlinearDutchDecay, OrderInfo,
+    OrderResolution, ResolvedInput, ResolvedOrder, ResolvedOutput,
+};
+pub use fill_event::{FillByLog, FillEvent, FillNotification};
+pub use order::{Order, OrderDecodeError, OrderSignatureError};
 pub use order_input::OrderInput;
 pub use order_notification::OrderNotification;
 pub use order_output::OrderOutput;
 pub use order_response::{OrderPayload, OrderResponse, OrderResponseError};
 pub use order_status::OrderStatus;
 pub use order_type::OrderType;
-pub use orders_request::OrdersRequest;
-pub use settled_amount::SettledAmount;
+pub use orders_request::{OrdersRequest, OrdersRequestBuilder, OrdersRequestError, Sort, SortKey};
+pub use permit2::{SignatureVerificationError, TokenPermissions};
+pub use priority_order::{PriorityCosignerData, PriorityInput, PriorityOrder, PriorityOutput};
+pub use settled_amount::{needs_resolution, SettleError, SettledAmount};
@@ -0,0 +1,240 @@
+use alloy::primitives::{I256, U256};
+
+use super::{
+    dutch_order::{InvalidOrderReason, NonlinearDutchDecay},
+    math::{bound, bounded_sub, mul_div_down, mul_div_up},
+};
+
+/// `relativeBlocks` packs the curve points as `uint16`s in a single `uint256`.
+const MAX_CURVE_POINTS: usize = 16;
+
+impl NonlinearDutchDecay {
+    /// Returns the block offset of the `n`th point of the curve.
+    pub fn relative_block(&self, n: usize) -> u16 {
+        ((self.relativeBlocks >> (n * 16)) & U256::from(u16::MAX)).to::<u16>()
+    }
+
+    /// Resolves `start_amount` over the curve at the given block, the same way the V3 reactor's
+    /// `NonlinearDutchDecayLib` does.
+    pub(crate) fn decay(
+        &self,
+        start_amount: U256,
+        decay_start_block: U256,
+        block_number: U256,
+        min_amount: U256,
+        max_amount: U256,
+        is_input: bool,
+    ) -> Result<U256, InvalidOrderReason> {
+        if self.relativeAmounts.len() > MAX_CURVE_POINTS {
+            return Err(InvalidOrderReason::InvalidDecayCurve);
+        }
+
+        if decay_start_block >= block_number || self.relativeAmounts.is_empty() {
+            return Ok(bound(start_amount, min_amount, max_amount));
+        }
+
+        // offsets past `u16::MAX` express a full decay
+        let block_delta = (block_number - decay_start_block)
+            .min(U256::from(u16::MAX))
+            .to::<u16>();
+
+        let (start_point, end_point, relative_start_amount, relative_end_amount) =
+            self.locate_curve_position(block_delta);
+
+        let curve_delta = linear_decay(
+            start_point,
+            end_point,
+            block_delta,
+            relative_start_amount,
+            relative_end_amount,
+            is_input,
+        )
+        .ok_or(InvalidOrderReason::Overflow)?;
+
+        Ok(bounded_sub(
+            start_amount,
+            curve_delta,
+            min_amount,
+            max_amount,
+        ))
+    }
+
+    /// Returns the two points of the curve surrounding the given block offset.
+    fn locate_curve_position(&self, current_relative_block: u16) -> (u16, u16, I256, I256) {
+        let mut relative_end_block = 0;
+
+        for (i, relative_amount) in self.relativeAmounts.iter().enumerate() {
+            relative_end_block = self.relative_block(i);
+
+            if relative_end_block >= current_relative_block {
+                return match i {
+                    0 => (0, relative_end_block, I256::ZERO, *relative_amount),
+                    i => (
+                        self.relative_block(i - 1),
+                        relative_end_block,
+                        self.relativeAmounts[i - 1],
+                        *relative_amount,
+                    ),
+                };
+            }
+        }
+
+        let last_amount = self.relativeAmounts[self.relativeAmounts.len() - 1];
+
+        (
+            relative_end_block,
+            relative_end_block,
+            last_amount,
+            last_amount,
+        )
+    }
+}
+
+/// Linearly interpolates between two points of the curve, rounding in favor of the swapper.
+fn linear_decay(
+    start_point: u16,
+    end_point: u16,
+    current_point: u16,
+    start_amount: I256,
+    end_amount: I256,
+    is_input: bool,
+) -> Option<I256> {
+    if current_point >= end_point {
+        return Some(end_amount);
+    }
+
+    let elapsed = U256::from(current_point - start_point);
+    let duration = U256::from(end_point - start_point);
+
+    let delta = if end_amount < start_amount {
+        let diff = start_amount.checked_sub(end_amount)?.into_raw();
+        let decay = if is_input {
+            mul_div_down(diff, elapsed, duration)?
+        } else {
+            mul_div_up(diff, elapsed, duration)?
+        };
+
+        I256::try_from(decay).ok()?.checked_neg()?
+    } else {
+        let diff = end_amount.checked_sub(start_amount)?.into_raw();
+        let decay = if is_input {
+            mul_div_up(diff, elapsed, duration)?
+        } else {
+            mul_div_down(diff, elapsed, duration)?
+        };
+
+        I256::try_from(decay).ok()?
+    };
+
+    start_amount.checked_add(delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{I256, U256};
+
+    use crate::types::dutch_order::{InvalidOrderReason, NonlinearDutchDecay};
+
+    fn curve(blocks: &[u16], amounts: &[i64]) -> NonlinearDutchDecay {
+        let relative_blocks = blocks
+            .iter()
+            .enumerate()
+            .fold(U256::ZERO, |packed, (i, block)| {
+                packed | (U256::from(*block) << (i * 16))
+            });
+
+        NonlinearDutchDecay {
+            relativeBlocks: relative_blocks,
+            relativeAmounts: amounts
+                .iter()
+                .map(|a| I256::try_from(*a).unwrap())
+                .collect(),
+        }
+    }
+
+    fn decay_output(curve: &NonlinearDutchDecay, start_amount: u64, block_offset: u64) -> U256 {
+        curve
+            .decay(
+                U256::from(start_amount),
+                U256::from(100),
+                U256::from(100 + block_offset),
+                U256::ZERO,
+                U256::MAX,
+                false,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_relative_blocks_unpacking() {
+        let curve = curve(&[1, 10, 65535], &[0, 0, 0]);
+
+        assert_eq!(curve.relative_block(0), 1);
+        assert_eq!(curve.relative_block(1), 10);
+        assert_eq!(curve.relative_block(2), 65535);
+        assert_eq!(curve.relative_block(3), 0);
+    }
+
+    #[test]
+    fn test_single_point_curve() {
+        let curve = curve(&[100], &[1000]);
+
+        assert_eq!(decay_output(&curve, 2000, 0), U256::from(2000));
+        assert_eq!(decay_output(&curve, 2000, 50), U256::from(1500));
+        assert_eq!(decay_output(&curve, 2000, 100), U256::from(1000));
+        assert_eq!(decay_output(&curve, 2000, 200), U256::from(1000));
+    }
+
+    #[test]
+    fn test_multi_point_curve() {
+        let curve = curve(&[100, 200, 300], &[1000, 0, 2000]);
+
+        assert_eq!(decay_output(&curve, 3000, 50), U256::from(2500));
+        assert_eq!(decay_output(&curve, 3000, 150), U256::from(2500));
+        assert_eq!(decay_output(&curve, 3000, 250), U256::from(2000));
+        assert_eq!(decay_output(&curve, 3000, 400), U256::from(1000));
+    }
+
+    #[test]
+    fn test_output_rounds_in_favor_of_swapper() {
+        let curve = curve(&[3], &[1000]);
+
+        // 1000 / 3 decays by 333.33, the output keeps the extra unit
+        assert_eq!(decay_output(&curve, 2000, 1), U256::from(1667));
+    }
+
+    #[test]
+    fn test_input_is_bounded_by_max_amount() {
+        let curve = curve(&[100], &[-1000]);
+
+        let decayed = curve
+            .decay(
+                U256::from(1000),
+                U256::from(100),
+                U256::from(150),
+                U256::ZERO,
+                U256::from(1200),
+                true,
+            )
+            .unwrap();
+
+        assert_eq!(decayed, U256::from(1200));
+    }
+
+    #[test]
+    fn test_too_many_points() {
+        let curve = curve(&[1; 17], &[0; 17]);
+
+        assert!(matches!(
+            curve.decay(
+                U256::from(1000),
+                U256::from(100),
+                U256::from(150),
+                U256::ZERO,
+                U256::MAX,
+                false
+            ),
+            Err(InvalidOrderReason::InvalidDecayCurve)
+        ));
+    }
+}
@@ -1,8 +1,11 @@
-use alloy::primitives::{Bytes, B256};
-use anyhow::{bail, Error};
+use alloy::primitives::{Address, Bytes, B256, U256};
 use serde::Deserialize;
+use thiserror::Error;
 
-use super::{DutchOrder, OrderInput, OrderOutput, OrderStatus, OrderType, SettledAmount};
+use super::{
+    DutchOrder, OrderInput, OrderOutput, OrderResolution, OrderStatus, OrderType, PriorityOrder,
+    SettledAmount, SignatureVerificationError,
+};
 
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -21,21 +24,141 @@ pub struct Order {
     pub created_at: u64,
 }
 
+impl Order {
+    /// Computes the order hash from the encoded order. It should match `order_hash`, unless the
+    /// API returned inconsistent data.
+    pub fn compute_hash(&self) -> Result<B256, OrderDecodeError> {
+        match self.order_type {
+            OrderType::Priority => Ok(PriorityOrder::try_from(self)?.hash()),
+            _ => Ok(DutchOrder::try_from(self)?.hash()),
+        }
+    }
+
+    /// Checks the order was signed by its swapper, through Permit2.
+    ///
+    /// Orders signed by smart contract wallets through EIP-1271 can't be verified offline, see
+    /// [`DutchOrder::verify_signature`].
+    pub fn verify_signature(&self) -> Result<(), OrderSignatureError> {
+        match self.order_type {
+            OrderType::Priority => {
+                PriorityOrder::try_from(self)?.verify_signature(&self.signature, self.chain_id)?
+            }
+            _ => DutchOrder::try_from(self)?.verify_signature(&self.signature, self.chain_id)?,
+        }
+
+        Ok(())
+    }
+
+    /// Returns the reactor the order must be filled through, from the encoded order.
+    pub fn reactor(&self) -> Result<Address, OrderDecodeError> {
+        match self.order_type {
+            OrderType::Priority => Ok(PriorityOrder::try_from(self)?.info.reactor),
+            _ => Ok(DutchOrder::try_from(self)?.info().reactor),
+        }
+    }
+
+    /// Returns the timestamp after which the order can't be filled anymore, from the encoded
+    /// order.
+    pub fn deadline(&self) -> Result<U256, OrderDecodeError> {
+        match self.order_type {
+            OrderType::Priority => Ok(PriorityOrder::try_from(self)?.info.deadline),
+            _ => Ok(DutchOrder::try_from(self)?.deadline()),
+        }
+    }
+
+    /// Resolves the order the way its reactor would in the given block, for a transaction sent
+    /// by `filler` paying `priority_fee` wei per gas above the base fee.
+    pub fn resolve(
+        &self,
+        timestamp: u64,
+        block_number: u64,
+        base_fee: U256,
+        priority_fee: U256,
+        filler: Address,
+    ) -> Result<OrderResolution, OrderDecodeError> {
+        match self.order_type {
+            OrderType::Priority => {
+                Ok(PriorityOrder::try_from(self)?.resolve(timestamp, block_number, priority_fee))
+            }
+            _ => Ok(DutchOrder::try_from(self)?.resolve_at_block(
+                timestamp,
+                block_number,
+                base_fee,
+                filler,
+            )),
+        }
+    }
+}
+
 impl TryFrom<&Order> for DutchOrder {
-    type Error = Error;
+    type Error = OrderDecodeError;
 
     fn try_from(order: &Order) -> Result<Self, Self::Error> {
         match &order.order_type {
             OrderType::Dutch => DutchOrder::try_from_v1(&order.encoded_order),
             OrderType::DutchV2 => DutchOrder::try_from_v2(&order.encoded_order),
-            ty => bail!("Order of type '{ty:?}' can't be decoded"),
+            OrderType::DutchV3 => DutchOrder::try_from_v3(&order.encoded_order),
+            ty => Err(OrderDecodeError::UnsupportedType(*ty)),
         }
     }
 }
 
+impl TryFrom<&Order> for PriorityOrder {
+    type Error = OrderDecodeError;
+
+    fn try_from(order: &Order) -> Result<Self, Self::Error> {
+        match &order.order_type {
+            OrderType::Priority => PriorityOrder::try_from_encoded(&order.encoded_order),
+            ty => Err(OrderDecodeError::UnsupportedType(*ty)),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum OrderDecodeError {
+    #[error("Failed to decode the encoded order: {0}")]
+    Abi(#[from] alloy::sol_types::Error),
+    #[error("Orders of type '{0:?}' can't be decoded")]
+    UnsupportedType(OrderType),
+}
+
+#[derive(Error, Debug)]
+pub enum OrderSignatureError {
+    #[error(transparent)]
+    Decode(#[from] OrderDecodeError),
+    #[error(transparent)]
+    Signature(#[from] SignatureVerificationError),
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Order;
+    use crate::{
+        fixtures::{DUTCH_V1_ORDER, DUTCH_V1_SIGNATURE},
+        types::{DutchOrder, OrderType, PriorityOrder},
+    };
+
+    use super::{Order, OrderDecodeError};
+
+    #[test]
+    fn decode_unsupported_type() {
+        let order = Order {
+            order_type: OrderType::Limit,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            DutchOrder::try_from(&order),
+            Err(OrderDecodeError::UnsupportedType(OrderType::Limit))
+        ));
+        assert!(matches!(
+            PriorityOrder::try_from(&order),
+            Err(OrderDecodeError::UnsupportedType(OrderType::Limit))
+        ));
+        assert!(matches!(
+            order.compute_hash(),
+            Err(OrderDecodeError::UnsupportedType(OrderType::Limit))
+        ));
+    }
 
     #[test]
     fn deserialize_order() {
@@ -49,8 +172,6 @@ mod tests {
                   "token":"0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
                }
             ],
-            "encodedOrder":"0x000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000001200000000000000000000000000000000000000000000000000000000064b51b750000000000000000000000000000000000000000000000000000000064b51b75000000000000000000000000b507d4ef5ed7a01e37cb578f497329cdb3c273a50000000000000000000000000000000000000000000000000000000000002710000000000000000000000000111111111117dc0aa78b770fa6a738034120c3020000000000000000000000000000000000000000000000056bc75e2d631000000000000000000000000000000000000000000000000000056bc75e2d631000000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000e80bf394d190851e215d5f67b67f8f5a52783f1e000000000000000000000000b8bff65b2eeb60d6b37312ca0740a742d5e7f95500000000000000000000000000000000000000000000000000000189635c5eac0000000000000000000000000000000000000000000000000000000064b51b75000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000c000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc20000000000000000000000000000000000000000000000000039a65e493e91140000000000000000000000000000000000000000000000000039a65e493e9114000000000000000000000000b8bff65b2eeb60d6b37312ca0740a742d5e7f955",
-            "signature":"0x3dd85ac7743719d3d5275e21062ef8e2d98acbf8b5ceb1e0436c3dc70cb16d812de7df3c9e15dc56295d563b2c36798155dae63abb074df6aa3d6e1cdc6257c91b",
             "input":{
                "endAmount":"100000000000000000000",
                "token":"0x111111111117dC0aa78b770fA6A738034120C302",
@@ -64,11 +185,17 @@ mod tests {
          }"#;
 
         // Parse the string of data into serde_json::Value.
-        let order = serde_json::from_str::<Order>(data).unwrap();
+        let mut value = serde_json::from_str::<serde_json::Value>(data).unwrap();
+        value["encodedOrder"] = DUTCH_V1_ORDER.into();
+        value["signature"] = DUTCH_V1_SIGNATURE.into();
+
+        let order = serde_json::from_value::<Order>(value).unwrap();
 
         assert_eq!(
             order.input.start_amount.to_string(),
             "100000000000000000000"
         );
+        assert_eq!(order.compute_hash().unwrap(), order.order_hash);
+        order.verify_signature().unwrap();
     }
 }
@@ -0,0 +1,231 @@
+use alloy::{primitives::B256, sol_types::SolStruct};
+
+use super::{
+    dutch_order::{DutchOrderV1, DutchOrderV2, OrderInfo},
+    DutchOrder, DutchOrderV3, PriorityOrder,
+};
+
+/// The witness types the reactors hash orders with. They differ from the encoded orders, as
+/// inputs are flattened and cosigner data is left out.
+pub(super) mod witness {
+    use alloy::sol;
+
+    use crate::types::{
+        dutch_order::OrderInfo, DutchOutput, NonlinearDutchDecay, PriorityInput, PriorityOutput,
+    };
+
+    sol! {
+        struct ExclusiveDutchOrder {
+            OrderInfo info;
+            uint256 decayStartTime;
+            uint256 decayEndTime;
+            address exclusiveFiller;
+            uint256 exclusivityOverrideBps;
+            address inputToken;
+            uint256 inputStartAmount;
+            uint256 inputEndAmount;
+            DutchOutput[] outputs;
+        }
+
+        struct V2DutchOrder {
+            OrderInfo info;
+            address cosigner;
+            address baseInputToken;
+            uint256 baseInputStartAmount;
+            uint256 baseInputEndAmount;
+            DutchOutput[] baseOutputs;
+        }
+
+        struct V3DutchInput {
+            address token;
+            uint256 startAmount;
+            NonlinearDutchDecay curve;
+            uint256 maxAmount;
+            uint256 adjustmentPerGweiBaseFee;
+        }
+
+        struct V3DutchOutput {
+            address token;
+            uint256 startAmount;
+            NonlinearDutchDecay curve;
+            address recipient;
+            uint256 minAmount;
+            uint256 adjustmentPerGweiBaseFee;
+        }
+
+        struct V3DutchOrder {
+            OrderInfo info;
+            address cosigner;
+            uint256 startingBaseFee;
+            V3DutchInput baseInput;
+            V3DutchOutput[] baseOutputs;
+        }
+
+        struct PriorityOrder {
+            OrderInfo info;
+            address cosigner;
+            uint256 auctionStartBlock;
+            uint256 baselinePriorityFeeWei;
+            PriorityInput input;
+            PriorityOutput[] outputs;
+        }
+    }
+}
+
+impl DutchOrder {
+    /// Computes the order hash the same way the reactor does, i.e. the EIP-712 struct hash of
+    /// the order witness.
+    pub fn hash(&self) -> B256 {
+        match self {
+            DutchOrder::V1(order) => order.hash(),
+            DutchOrder::V2(order) => order.hash(),
+            DutchOrder::V3(order) => order.hash(),
+        }
+    }
+
+    pub fn info(&self) -> &OrderInfo {
+        match self {
+            DutchOrder::V1(order) => &order.info,
+            DutchOrder::V2(order) => &order.info,
+            DutchOrder::V3(order) => &order.info,
+        }
+    }
+}
+
+impl DutchOrderV1 {
+    pub fn hash(&self) -> B256 {
+        self.witness().eip712_hash_struct()
+    }
+
+    pub(crate) fn witness(&self) -> witness::ExclusiveDutchOrder {
+        witness::ExclusiveDutchOrder {
+            info: self.info.clone(),
+            decayStartTime: self.decayStartTime,
+            decayEndTime: self.decayEndTime,
+            exclusiveFiller: self.exclusiveFiller,
+            exclusivityOverrideBps: self.exclusivityOverrideBps,
+            inputToken: self.input.token,
+            inputStartAmount: self.input.startAmount,
+            inputEndAmount: self.input.endAmount,
+            outputs: self.outputs.clone(),
+        }
+    }
+}
+
+impl DutchOrderV2 {
+    pub fn hash(&self) -> B256 {
+        self.witness().eip712_hash_struct()
+    }
+
+    pub(crate) fn witness(&self) -> witness::V2DutchOrder {
+        witness::V2DutchOrder {
+            info: self.info.clone(),
+            cosigner: self.cosigner,
+            baseInputToken: self.input.token,
+            baseInputStartAmount: self.input.startAmount,
+            baseInputEndAmount: self.input.endAmount,
+            baseOutputs: self.outputs.clone(),
+        }
+    }
+}
+
+impl DutchOrderV3 {
+    pub fn hash(&self) -> B256 {
+        self.witness().eip712_hash_struct()
+    }
+
+    pub(crate) fn witness(&self) -> witness::V3DutchOrder {
+        witness::V3DutchOrder {
+            info: self.info.clone(),
+            cosigner: self.cosigner,
+            startingBaseFee: self.startingBaseFee,
+            baseInput: witness::V3DutchInput {
+                token: self.input.token,
+                startAmount: self.input.startAmount,
+                curve: self.input.curve.clone(),
+                maxAmount: self.input.maxAmount,
+                adjustmentPerGweiBaseFee: self.input.adjustmentPerGweiBaseFee,
+            },
+            baseOutputs: self
+                .outputs
+                .iter()
+                .map(|output| witness::V3DutchOutput {
+                    token: output.token,
+                    startAmount: output.startAmount,
+                    curve: output.curve.clone(),
+                    recipient: output.recipient,
+                    minAmount: output.minAmount,
+                    adjustmentPerGweiBaseFee: output.adjustmentPerGweiBaseFee,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl PriorityOrder {
+    /// Computes the order hash the same way the priority reactor does, i.e. the EIP-712 struct
+    /// hash of the order witness.
+    pub fn hash(&self) -> B256 {
+        self.witness().eip712_hash_struct()
+    }
+
+    pub(crate) fn witness(&self) -> witness::PriorityOrder {
+        witness::PriorityOrder {
+            info: self.info.clone(),
+            cosigner: self.cosigner,
+            auctionStartBlock: self.auctionStartBlock,
+            baselinePriorityFeeWei: self.baselinePriorityFeeWei,
+            input: self.input.clone(),
+            outputs: self.outputs.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{b256, Bytes},
+        sol_types::SolStruct,
+    };
+
+    use crate::{fixtures::DUTCH_V1_ORDER, types::DutchOrder};
+
+    use super::witness;
+
+    #[test]
+    fn test_v1_hash() {
+        let encoded: Bytes = DUTCH_V1_ORDER.parse().unwrap();
+
+        let order = DutchOrder::try_from_v1(&encoded).unwrap();
+
+        assert_eq!(
+            order.hash(),
+            b256!("b057f8a9f0edcd0bd7156015232785cdc2c4d8a1e84be06169f4681d483b6709")
+        );
+    }
+
+    #[test]
+    fn test_witness_types() {
+        assert_eq!(
+            witness::V2DutchOrder::eip712_encode_type(),
+            "V2DutchOrder(OrderInfo info,address cosigner,address baseInputToken,uint256 baseInputStartAmount,uint256 baseInputEndAmount,DutchOutput[] baseOutputs)\
+            DutchOutput(address token,uint256 startAmount,uint256 endAmount,address recipient)\
+            OrderInfo(address reactor,address swapper,uint256 nonce,uint256 deadline,address additionalValidationContract,bytes additionalValidationData)"
+        );
+        assert_eq!(
+            witness::V3DutchOrder::eip712_encode_type(),
+            "V3DutchOrder(OrderInfo info,address cosigner,uint256 startingBaseFee,V3DutchInput baseInput,V3DutchOutput[] baseOutputs)\
+            NonlinearDutchDecay(uint256 relativeBlocks,int256[] relativeAmounts)\
+            OrderInfo(address reactor,address swapper,uint256 nonce,uint256 deadline,address additionalValidationContract,bytes additionalValidationData)\
+            V3DutchInput(address token,uint256 startAmount,NonlinearDutchDecay curve,uint256 maxAmount,uint256 adjustmentPerGweiBaseFee)\
+            V3DutchOutput(address token,uint256 startAmount,NonlinearDutchDecay curve,address recipient,uint256 minAmount,uint256 adjustmentPerGweiBaseFee)"
+        );
+        assert_eq!(
+            witness::PriorityOrder::eip712_encode_type(),
+            "PriorityOrder(OrderInfo info,address cosigner,uint256 auctionStartBlock,uint256 baselinePriorityFeeWei,PriorityInput input,PriorityOutput[] outputs)\
+            OrderInfo(address reactor,address swapper,uint256 nonce,uint256 deadline,address additionalValidationContract,bytes additionalValidationData)\
+            PriorityInput(address token,uint256 amount,uint256 mpsPerPriorityFeeWei)\
+            PriorityOutput(address token,uint256 amount,uint256 mpsPerPriorityFeeWei,address recipient)"
+        );
+    }
+}
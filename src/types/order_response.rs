@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+use reqwest::StatusCode;
 use serde::Deserialize;
 use thiserror::Error;
 
@@ -33,6 +34,20 @@ pub struct OrderPayload {
 pub struct OrderResponseError {
     error_code: String,
     details: Option<String>,
+    #[serde(skip)]
+    status: Option<StatusCode>,
+}
+
+impl OrderResponseError {
+    /// The HTTP status the API answered with.
+    pub fn status(&self) -> Option<StatusCode> {
+        self.status
+    }
+
+    pub(crate) fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
 }
 
 impl TryFrom<OrderResponse> for Vec<Order> {
@@ -57,6 +72,8 @@ impl Display for OrderResponseError {
 
 #[cfg(test)]
 mod tests {
+    use crate::fixtures::{DUTCH_V1_ORDER, DUTCH_V1_SIGNATURE};
+
     use super::OrderResponse;
 
     #[test]
@@ -73,8 +90,6 @@ mod tests {
                         "token":"0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
                      }
                   ],
-                  "encodedOrder":"0x000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000001200000000000000000000000000000000000000000000000000000000064b51b750000000000000000000000000000000000000000000000000000000064b51b75000000000000000000000000b507d4ef5ed7a01e37cb578f497329cdb3c273a50000000000000000000000000000000000000000000000000000000000002710000000000000000000000000111111111117dc0aa78b770fa6a738034120c3020000000000000000000000000000000000000000000000056bc75e2d631000000000000000000000000000000000000000000000000000056bc75e2d631000000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000e80bf394d190851e215d5f67b67f8f5a52783f1e000000000000000000000000b8bff65b2eeb60d6b37312ca0740a742d5e7f95500000000000000000000000000000000000000000000000000000189635c5eac0000000000000000000000000000000000000000000000000000000064b51b75000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000c000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc20000000000000000000000000000000000000000000000000039a65e493e91140000000000000000000000000000000000000000000000000039a65e493e9114000000000000000000000000b8bff65b2eeb60d6b37312ca0740a742d5e7f955",
-                  "signature":"0x3dd85ac7743719d3d5275e21062ef8e2d98acbf8b5ceb1e0436c3dc70cb16d812de7df3c9e15dc56295d563b2c36798155dae63abb074df6aa3d6e1cdc6257c91b",
                   "input":{
                      "endAmount":"100000000000000000000",
                      "token":"0x111111111117dC0aa78b770fA6A738034120C302",
@@ -112,7 +127,11 @@ mod tests {
          }"#;
 
         // Parse the string of data into serde_json::Value.
-        let response = serde_json::from_str::<OrderResponse>(data).unwrap();
+        let mut value = serde_json::from_str::<serde_json::Value>(data).unwrap();
+        value["orders"][0]["encodedOrder"] = DUTCH_V1_ORDER.into();
+        value["orders"][0]["signature"] = DUTCH_V1_SIGNATURE.into();
+
+        let response = serde_json::from_value::<OrderResponse>(value).unwrap();
 
         assert!(matches!(response, OrderResponse::Orders { .. }));
     }
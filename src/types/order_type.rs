@@ -1,10 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OrderType {
     Dutch,
     #[serde(rename = "Dutch_V2")]
     DutchV2,
+    #[serde(rename = "Dutch_V3")]
+    DutchV3,
     Limit,
     Relay,
     #[default]
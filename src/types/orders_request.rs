@@ -1,53 +1,373 @@
-use base64::{engine::general_purpose::STANDARD, Engine};
-use serde::Serialize;
+use std::fmt::Display;
 
-use super::{Order, OrderStatus, OrderType};
+use alloy::primitives::{Address, B256};
+use serde::{Serialize, Serializer};
+use thiserror::Error;
 
+use super::{Cursor, CursorError, Order, OrderStatus, OrderType};
+
+/// The query parameters of the `/v2/orders` endpoint. Prefer [`OrdersRequest::builder`], which
+/// rejects the combinations the API doesn't accept.
 #[derive(Debug, Clone, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrdersRequest {
     pub chain_id: Option<u64>,
     pub order_type: Option<OrderType>,
     pub order_status: Option<OrderStatus>,
+    /// Prefer [`OrdersRequestBuilder::order_hash`], which formats a typed hash.
     pub order_hash: Option<String>,
+    /// Looks several orders up at once.
+    #[serde(serialize_with = "serialize_order_hashes")]
+    pub order_hashes: Option<Vec<B256>>,
+    pub swapper: Option<Address>,
+    pub filler: Option<Address>,
+    /// The maximum number of orders per page.
+    pub limit: Option<u64>,
+    /// The field `sort` applies to, required along with it.
+    pub sort_key: Option<SortKey>,
+    pub sort: Option<Sort>,
+    /// Whether to sort the orders in descending order of `sort_key`.
+    pub desc: Option<bool>,
     pub cursor: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortKey {
+    CreatedAt,
+}
+
+/// A range of values of the sort key, e.g. of timestamps for [`SortKey::CreatedAt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    Gt(u64),
+    Gte(u64),
+    Lt(u64),
+    Lte(u64),
+    /// Both bounds included.
+    Between(u64, u64),
+}
+
+impl Display for Sort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Sort::Gt(value) => write!(f, "gt({value})"),
+            Sort::Gte(value) => write!(f, "gte({value})"),
+            Sort::Lt(value) => write!(f, "lt({value})"),
+            Sort::Lte(value) => write!(f, "lte({value})"),
+            Sort::Between(start, end) => write!(f, "between({start},{end})"),
+        }
+    }
+}
+
+impl Serialize for Sort {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// The API expects the order hashes as a single comma separated parameter.
+fn serialize_order_hashes<S>(
+    order_hashes: &Option<Vec<B256>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match order_hashes {
+        Some(order_hashes) => {
+            let joined = order_hashes
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+
+            serializer.serialize_some(&joined)
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
 impl OrdersRequest {
+    pub fn builder() -> OrdersRequestBuilder {
+        OrdersRequestBuilder::default()
+    }
+
+    /// Checks the request is one the API accepts.
+    pub fn validate(&self) -> Result<(), OrdersRequestError> {
+        if self.chain_id.is_none()
+            && self.order_status.is_none()
+            && self.order_hash.is_none()
+            && self.order_hashes.is_none()
+            && self.swapper.is_none()
+            && self.filler.is_none()
+        {
+            return Err(OrdersRequestError::MissingFilter);
+        }
+
+        if self.order_hash.is_some() && self.order_hashes.is_some() {
+            return Err(OrdersRequestError::OrderHashAndOrderHashes);
+        }
+
+        if self
+            .order_hashes
+            .as_ref()
+            .is_some_and(|order_hashes| order_hashes.is_empty())
+        {
+            return Err(OrdersRequestError::EmptyOrderHashes);
+        }
+
+        if self.limit == Some(0) {
+            return Err(OrdersRequestError::ZeroLimit);
+        }
+
+        match (self.sort_key, self.sort) {
+            (Some(_), None) | (None, Some(_)) => return Err(OrdersRequestError::IncompleteSort),
+            (_, Some(Sort::Between(start, end))) if start > end => {
+                return Err(OrdersRequestError::InvalidSortRange { start, end })
+            }
+            _ => {}
+        }
+
+        if self.desc.is_some() && self.sort_key.is_none() {
+            return Err(OrdersRequestError::DescWithoutSortKey);
+        }
+
+        Ok(())
+    }
+
     pub fn with_cursor(mut self, cursor: Option<String>) -> Self {
         self.cursor = cursor;
         self
     }
-    /// We need to build a cursor form the last known order. For instance:
-    /// {"chainId_orderStatus":"1_filled","createdAt":1685115350,"orderHash":"0x8b984116a793011c9288f00ce0e3a5eb5bee9234e006de154551bc915d676654"}
-    pub fn cursor_from_order(&self, order: Option<&Order>) -> Option<String> {
-        order.map(|o| {
-            let (filter_key, filter_value) = match (self.chain_id, &self.order_status) {
-                (None, None) => panic!("At least one of chain id or order status"),
-                (None, Some(order_status)) => ("orderStatus", format!("{order_status}")),
-                (Some(chain_id), None) => ("chainId", chain_id.to_string()),
-                (Some(chain_id), Some(order_status)) => {
-                    ("chainId_orderStatus", format!("{chain_id}_{order_status}"))
-                }
-            };
-
-            let json = format!(
-                r#"{{"{filter_key}":"{filter_value}","createdAt":{},"orderHash":"{}"}}"#,
-                o.created_at, o.order_hash
-            );
-
-            STANDARD.encode(json.as_bytes())
-        })
+    /// Builds the cursor pointing after the given order, e.g. the last one of a page, in the
+    /// index the API queries for the filters of the request.
+    pub fn cursor_from_order(&self, order: &Order) -> Result<Cursor, CursorError> {
+        Cursor::from_order(self, order)
+    }
+}
+
+/// Builds an [`OrdersRequest`], validated once built.
+#[derive(Debug, Clone, Default)]
+pub struct OrdersRequestBuilder {
+    request: OrdersRequest,
+}
+
+impl OrdersRequestBuilder {
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.request.chain_id = Some(chain_id);
+        self
+    }
+
+    pub fn order_type(mut self, order_type: OrderType) -> Self {
+        self.request.order_type = Some(order_type);
+        self
+    }
+
+    pub fn order_status(mut self, order_status: OrderStatus) -> Self {
+        self.request.order_status = Some(order_status);
+        self
+    }
+
+    pub fn order_hash(mut self, order_hash: B256) -> Self {
+        self.request.order_hash = Some(order_hash.to_string());
+        self
+    }
+
+    pub fn order_hashes(mut self, order_hashes: impl IntoIterator<Item = B256>) -> Self {
+        self.request
+            .order_hashes
+            .get_or_insert_with(Vec::new)
+            .extend(order_hashes);
+        self
+    }
+
+    pub fn swapper(mut self, swapper: Address) -> Self {
+        self.request.swapper = Some(swapper);
+        self
+    }
+
+    pub fn filler(mut self, filler: Address) -> Self {
+        self.request.filler = Some(filler);
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.request.limit = Some(limit);
+        self
+    }
+
+    pub fn sort(mut self, sort_key: SortKey, sort: Sort) -> Self {
+        self.request.sort_key = Some(sort_key);
+        self.request.sort = Some(sort);
+        self
+    }
+
+    /// Only returns the orders created between the two timestamps included.
+    pub fn created_between(self, start: u64, end: u64) -> Self {
+        self.sort(SortKey::CreatedAt, Sort::Between(start, end))
+    }
+
+    pub fn desc(mut self, desc: bool) -> Self {
+        self.request.desc = Some(desc);
+        self
+    }
+
+    pub fn cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.request.cursor = Some(cursor.into());
+        self
+    }
+
+    pub fn build(self) -> Result<OrdersRequest, OrdersRequestError> {
+        self.request.validate()?;
+
+        Ok(self.request)
     }
 }
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OrdersRequestError {
+    #[error(
+        "At least one of chain id, order status, order hash(es), swapper or filler is required"
+    )]
+    MissingFilter,
+    #[error("Order hash and order hashes can't be combined")]
+    OrderHashAndOrderHashes,
+    #[error("Order hashes can't be empty")]
+    EmptyOrderHashes,
+    #[error("The limit must be positive")]
+    ZeroLimit,
+    #[error("Sort and sort key must be set together")]
+    IncompleteSort,
+    #[error("The sort range starts at {start}, after its end {end}")]
+    InvalidSortRange { start: u64, end: u64 },
+    #[error("Sorting in descending order requires a sort key")]
+    DescWithoutSortKey,
+}
+
 #[cfg(test)]
 mod tests {
-    use alloy::primitives::hex;
+    use alloy::primitives::{address, hex, B256};
 
     use crate::types::{Order, OrderStatus};
 
-    use super::OrdersRequest;
+    use super::{OrdersRequest, OrdersRequestError, Sort, SortKey};
+
+    fn query(request: &OrdersRequest) -> Option<String> {
+        reqwest::Client::new()
+            .get("https://api.uniswap.org/v2/orders")
+            .query(request)
+            .build()
+            .unwrap()
+            .url()
+            .query()
+            .map(ToString::to_string)
+    }
+
+    #[test]
+    fn test_swapper_history_query() {
+        let request = OrdersRequest::builder()
+            .swapper(address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"))
+            .limit(10)
+            .created_between(1689589146, 1689590645)
+            .desc(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            query(&request),
+            Some(String::from(
+                "swapper=0xb8bff65b2eeb60d6b37312ca0740a742d5e7f955&limit=10&sortKey=createdAt&sort=between%281689589146%2C1689590645%29&desc=true"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(
+            OrdersRequest::builder().build().unwrap_err(),
+            OrdersRequestError::MissingFilter
+        );
+        assert_eq!(
+            OrdersRequest::builder()
+                .order_hash(B256::ZERO)
+                .order_hashes([B256::ZERO])
+                .build()
+                .unwrap_err(),
+            OrdersRequestError::OrderHashAndOrderHashes
+        );
+        assert_eq!(
+            OrdersRequest::builder()
+                .order_hashes([])
+                .build()
+                .unwrap_err(),
+            OrdersRequestError::EmptyOrderHashes
+        );
+        assert_eq!(
+            OrdersRequest::builder()
+                .chain_id(1)
+                .created_between(2, 1)
+                .build()
+                .unwrap_err(),
+            OrdersRequestError::InvalidSortRange { start: 2, end: 1 }
+        );
+        assert_eq!(
+            OrdersRequest::builder()
+                .chain_id(1)
+                .desc(true)
+                .build()
+                .unwrap_err(),
+            OrdersRequestError::DescWithoutSortKey
+        );
+        assert_eq!(
+            OrdersRequest {
+                chain_id: Some(1),
+                sort_key: Some(SortKey::CreatedAt),
+                ..Default::default()
+            }
+            .validate()
+            .unwrap_err(),
+            OrdersRequestError::IncompleteSort
+        );
+        assert!(OrdersRequest::builder()
+            .chain_id(1)
+            .sort(SortKey::CreatedAt, Sort::Gte(1689589146))
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_order_hash_query() {
+        let request = OrdersRequest::builder()
+            .order_hash(B256::repeat_byte(0xaa))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            query(&request),
+            Some(format!("orderHash={}", B256::repeat_byte(0xaa)))
+        );
+    }
+
+    #[test]
+    fn test_order_hashes_query() {
+        let request = OrdersRequest {
+            order_hashes: Some(vec![B256::repeat_byte(0xaa), B256::repeat_byte(0xbb)]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            query(&request),
+            Some(format!(
+                "orderHashes={}%2C{}",
+                B256::repeat_byte(0xaa),
+                B256::repeat_byte(0xbb)
+            ))
+        );
+    }
 
     #[test]
     fn test_or_with_cursor_from_order() {
@@ -64,13 +384,11 @@ mod tests {
             ..Default::default()
         };
 
-        let cursor = request.cursor_from_order(Some(&order));
+        let cursor = request.cursor_from_order(&order).unwrap();
 
         assert_eq!(
-            cursor,
-            Some(String::from(
-                "eyJjaGFpbklkX29yZGVyU3RhdHVzIjoiMV9maWxsZWQiLCJjcmVhdGVkQXQiOjAsIm9yZGVySGFzaCI6IjB4MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDBhYSJ9"
-            ))
+            cursor.to_string(),
+            "eyJjaGFpbklkX29yZGVyU3RhdHVzIjoiMV9maWxsZWQiLCJjcmVhdGVkQXQiOjAsIm9yZGVySGFzaCI6IjB4MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDBhYSJ9"
         );
     }
 }
@@ -0,0 +1,294 @@
+use alloy::{
+    primitives::{Address, PrimitiveSignature, SignatureError, B256},
+    sol,
+    sol_types::{eip712_domain, SolStruct},
+};
+use thiserror::Error;
+
+use crate::reactor_config::PERMIT2_ADDRESS;
+
+use super::{DutchOrder, PriorityOrder};
+
+sol! {
+    #[derive(Debug)]
+    struct TokenPermissions {
+        address token;
+        uint256 amount;
+    }
+}
+
+/// Permit2 hashes the `PermitWitnessTransferFrom` struct with the order witness inlined, so each
+/// witness type needs its own struct, all sharing the same EIP-712 name.
+mod permit {
+    use alloy::sol;
+
+    use super::TokenPermissions;
+
+    pub(super) mod v1 {
+        use super::{sol, TokenPermissions};
+        use crate::types::order_hash::witness::ExclusiveDutchOrder;
+
+        sol! {
+            struct PermitWitnessTransferFrom {
+                TokenPermissions permitted;
+                address spender;
+                uint256 nonce;
+                uint256 deadline;
+                ExclusiveDutchOrder witness;
+            }
+        }
+    }
+
+    pub(super) mod v2 {
+        use super::{sol, TokenPermissions};
+        use crate::types::order_hash::witness::V2DutchOrder;
+
+        sol! {
+            struct PermitWitnessTransferFrom {
+                TokenPermissions permitted;
+                address spender;
+                uint256 nonce;
+                uint256 deadline;
+                V2DutchOrder witness;
+            }
+        }
+    }
+
+    pub(super) mod v3 {
+        use super::{sol, TokenPermissions};
+        use crate::types::order_hash::witness::V3DutchOrder;
+
+        sol! {
+            struct PermitWitnessTransferFrom {
+                TokenPermissions permitted;
+                address spender;
+                uint256 nonce;
+                uint256 deadline;
+                V3DutchOrder witness;
+            }
+        }
+    }
+
+    pub(super) mod priority {
+        use super::{sol, TokenPermissions};
+        use crate::types::order_hash::witness::PriorityOrder;
+
+        sol! {
+            struct PermitWitnessTransferFrom {
+                TokenPermissions permitted;
+                address spender;
+                uint256 nonce;
+                uint256 deadline;
+                PriorityOrder witness;
+            }
+        }
+    }
+}
+
+impl DutchOrder {
+    /// Computes the Permit2 `PermitWitnessTransferFrom` digest the swapper signed.
+    pub fn permit2_signing_hash(&self, chain_id: u64, permit2: Address) -> B256 {
+        let domain = eip712_domain! {
+            name: "Permit2",
+            chain_id: chain_id,
+            verifying_contract: permit2,
+        };
+
+        match self {
+            DutchOrder::V1(order) => permit::v1::PermitWitnessTransferFrom {
+                permitted: TokenPermissions {
+                    token: order.input.token,
+                    amount: order.input.endAmount,
+                },
+                spender: order.info.reactor,
+                nonce: order.info.nonce,
+                deadline: order.info.deadline,
+                witness: order.witness(),
+            }
+            .eip712_signing_hash(&domain),
+            DutchOrder::V2(order) => permit::v2::PermitWitnessTransferFrom {
+                permitted: TokenPermissions {
+                    token: order.input.token,
+                    amount: order.input.endAmount,
+                },
+                spender: order.info.reactor,
+                nonce: order.info.nonce,
+                deadline: order.info.deadline,
+                witness: order.witness(),
+            }
+            .eip712_signing_hash(&domain),
+            DutchOrder::V3(order) => permit::v3::PermitWitnessTransferFrom {
+                permitted: TokenPermissions {
+                    token: order.input.token,
+                    amount: order.input.maxAmount,
+                },
+                spender: order.info.reactor,
+                nonce: order.info.nonce,
+                deadline: order.info.deadline,
+                witness: order.witness(),
+            }
+            .eip712_signing_hash(&domain),
+        }
+    }
+
+    /// Checks the given signature was produced by the order's swapper.
+    ///
+    /// Only ECDSA signatures can be checked offline. Permit2 also accepts EIP-1271 signatures
+    /// from smart contract wallets, which are validated on-chain by the wallet itself: they're
+    /// reported as [`SignatureVerificationError::UnsupportedSignature`] when they aren't shaped
+    /// like an ECDSA signature, and as [`SignatureVerificationError::InvalidSigner`] otherwise.
+    pub fn verify_signature(
+        &self,
+        signature: &[u8],
+        chain_id: u64,
+    ) -> Result<(), SignatureVerificationError> {
+        let signing_hash = self.permit2_signing_hash(chain_id, PERMIT2_ADDRESS);
+
+        verify_swapper(signing_hash, signature, self.info().swapper)
+    }
+}
+
+impl PriorityOrder {
+    /// Computes the Permit2 `PermitWitnessTransferFrom` digest the swapper signed.
+    pub fn permit2_signing_hash(&self, chain_id: u64, permit2: Address) -> B256 {
+        let domain = eip712_domain! {
+            name: "Permit2",
+            chain_id: chain_id,
+            verifying_contract: permit2,
+        };
+
+        permit::priority::PermitWitnessTransferFrom {
+            permitted: TokenPermissions {
+                token: self.input.token,
+                amount: self.input.amount,
+            },
+            spender: self.info.reactor,
+            nonce: self.info.nonce,
+            deadline: self.info.deadline,
+            witness: self.witness(),
+        }
+        .eip712_signing_hash(&domain)
+    }
+
+    /// Checks the given signature was produced by the order's swapper.
+    ///
+    /// Only ECDSA signatures can be checked offline. Permit2 also accepts EIP-1271 signatures
+    /// from smart contract wallets, which are validated on-chain by the wallet itself: they're
+    /// reported as [`SignatureVerificationError::UnsupportedSignature`] when they aren't shaped
+    /// like an ECDSA signature, and as [`SignatureVerificationError::InvalidSigner`] otherwise.
+    pub fn verify_signature(
+        &self,
+        signature: &[u8],
+        chain_id: u64,
+    ) -> Result<(), SignatureVerificationError> {
+        let signing_hash = self.permit2_signing_hash(chain_id, PERMIT2_ADDRESS);
+
+        verify_swapper(signing_hash, signature, self.info.swapper)
+    }
+}
+
+/// Checks the swapper signed the given Permit2 digest, which can only be done offline for ECDSA
+/// signatures.
+fn verify_swapper(
+    signing_hash: B256,
+    signature: &[u8],
+    swapper: Address,
+) -> Result<(), SignatureVerificationError> {
+    if !matches!(signature.len(), 64 | 65) {
+        return Err(SignatureVerificationError::UnsupportedSignature {
+            len: signature.len(),
+        });
+    }
+
+    verify_signer(signing_hash, signature, swapper)
+}
+
+/// Recovers the signer of the given digest, and checks it is the expected one.
+pub(crate) fn verify_signer(
+    signing_hash: B256,
+    signature: &[u8],
+    expected: Address,
+) -> Result<(), SignatureVerificationError> {
+    let recovered = parse_signature(signature)?.recover_address_from_prehash(&signing_hash)?;
+
+    if recovered != expected {
+        return Err(SignatureVerificationError::InvalidSigner {
+            expected,
+            recovered,
+        });
+    }
+
+    Ok(())
+}
+
+/// Parses either a 65 bytes `(r, s, v)` signature or a 64 bytes EIP-2098 compact one, as
+/// Permit2 accepts both.
+fn parse_signature(signature: &[u8]) -> Result<PrimitiveSignature, SignatureError> {
+    if signature.len() == 64 {
+        return Ok(PrimitiveSignature::from_erc2098(signature));
+    }
+
+    PrimitiveSignature::try_from(signature)
+}
+
+#[derive(Error, Debug)]
+pub enum SignatureVerificationError {
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(#[from] SignatureError),
+    #[error("Signed by {recovered} instead of {expected}")]
+    InvalidSigner {
+        expected: Address,
+        recovered: Address,
+    },
+    /// A signature which isn't ECDSA, most likely an EIP-1271 one from a smart contract wallet:
+    /// only the wallet can tell whether it's valid.
+    #[error("Unsupported signature of {len} bytes, which can only be verified on-chain")]
+    UnsupportedSignature { len: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{address, Bytes};
+
+    use crate::{
+        fixtures::{DUTCH_V1_ORDER, DUTCH_V1_SIGNATURE},
+        types::DutchOrder,
+    };
+
+    use super::SignatureVerificationError;
+
+    #[test]
+    fn test_verify_signature() {
+        let order = DutchOrder::try_from_v1(&DUTCH_V1_ORDER.parse().unwrap()).unwrap();
+        let signature: Bytes = DUTCH_V1_SIGNATURE.parse().unwrap();
+
+        order.verify_signature(&signature, 1).unwrap();
+    }
+
+    #[test]
+    fn test_verify_signature_on_wrong_chain() {
+        let order = DutchOrder::try_from_v1(&DUTCH_V1_ORDER.parse().unwrap()).unwrap();
+        let signature: Bytes = DUTCH_V1_SIGNATURE.parse().unwrap();
+
+        let err = order.verify_signature(&signature, 42161).unwrap_err();
+
+        assert!(matches!(
+            err,
+            SignatureVerificationError::InvalidSigner { expected, .. }
+                if expected == address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955")
+        ));
+    }
+
+    #[test]
+    fn test_verify_contract_signature() {
+        let order = DutchOrder::try_from_v1(&DUTCH_V1_ORDER.parse().unwrap()).unwrap();
+
+        // e.g. a Safe signature, concatenating several owner signatures
+        let err = order.verify_signature(&[1; 130], 1).unwrap_err();
+
+        assert!(matches!(
+            err,
+            SignatureVerificationError::UnsupportedSignature { len: 130 }
+        ));
+    }
+}
@@ -0,0 +1,257 @@
+use alloy::{
+    primitives::{Bytes, U256},
+    sol,
+    sol_types::SolValue,
+};
+
+use super::{
+    dutch_order::OrderInfo,
+    math::{mul_div_down, mul_div_up},
+    InvalidOrderReason, OrderDecodeError, OrderResolution, ResolvedInput, ResolvedOrder,
+    ResolvedOutput,
+};
+
+sol! {
+    #[derive(Debug)]
+    struct PriorityInput {
+        address token;
+        uint256 amount;
+        uint256 mpsPerPriorityFeeWei;
+    }
+
+    #[derive(Debug)]
+    struct PriorityOutput {
+        address token;
+        uint256 amount;
+        uint256 mpsPerPriorityFeeWei;
+        address recipient;
+    }
+
+    #[derive(Debug)]
+    struct PriorityCosignerData {
+        uint256 auctionTargetBlock;
+    }
+
+    #[derive(Debug)]
+    struct PriorityOrder {
+        OrderInfo info;
+        address cosigner;
+        uint256 auctionStartBlock;
+        uint256 baselinePriorityFeeWei;
+        PriorityInput input;
+        PriorityOutput[] outputs;
+        PriorityCosignerData cosignerData;
+        bytes cosignature;
+    }
+}
+
+/// Milli-basis-points, the unit priority orders are scaled in.
+const MPS: u64 = 10_000_000;
+
+impl PriorityOrder {
+    pub fn try_from_encoded(encoded: &Bytes) -> Result<Self, OrderDecodeError> {
+        Ok(Self::abi_decode(encoded, true)?)
+    }
+
+    /// Returns the block the auction starts at, taking the cosigner's target block into account.
+    pub fn auction_start_block(&self) -> U256 {
+        let target_block = self.cosignerData.auctionTargetBlock;
+
+        if !self.cosigner.is_zero()
+            && !target_block.is_zero()
+            && target_block < self.auctionStartBlock
+        {
+            target_block
+        } else {
+            self.auctionStartBlock
+        }
+    }
+
+    /// Resolves the order the same way the priority reactor does, for a transaction paying the
+    /// given priority fee (`tx.gasprice - block.basefee`) in wei.
+    pub fn resolve(
+        &self,
+        timestamp: u64,
+        block_number: u64,
+        priority_fee: U256,
+    ) -> OrderResolution {
+        self.try_resolve(timestamp, block_number, priority_fee)
+            .unwrap_or_else(OrderResolution::Invalid)
+    }
+
+    fn try_resolve(
+        &self,
+        timestamp: u64,
+        block_number: u64,
+        priority_fee: U256,
+    ) -> Result<OrderResolution, InvalidOrderReason> {
+        if self.info.deadline.lt(&U256::from(timestamp)) {
+            return Ok(OrderResolution::Expired);
+        };
+
+        if U256::from(block_number) < self.auction_start_block() {
+            return Ok(OrderResolution::NotFillable);
+        }
+
+        // only the input or the outputs can be scaled
+        if !self.input.mpsPerPriorityFeeWei.is_zero()
+            && self
+                .outputs
+                .iter()
+                .any(|output| !output.mpsPerPriorityFeeWei.is_zero())
+        {
+            return Err(InvalidOrderReason::InputOutputScaled);
+        }
+
+        let priority_fee = priority_fee.saturating_sub(self.baselinePriorityFeeWei);
+        let mps = U256::from(MPS);
+
+        // the input decreases as the priority fee increases
+        let input_scaling = priority_fee
+            .checked_mul(self.input.mpsPerPriorityFeeWei)
+            .ok_or(InvalidOrderReason::Overflow)?;
+
+        let input_amount = if input_scaling >= mps {
+            U256::ZERO
+        } else {
+            mul_div_down(self.input.amount, mps - input_scaling, mps)
+                .ok_or(InvalidOrderReason::Overflow)?
+        };
+
+        // the outputs increase as the priority fee increases
+        let mut outputs = Vec::with_capacity(self.outputs.len());
+
+        for output in &self.outputs {
+            let amount = priority_fee
+                .checked_mul(output.mpsPerPriorityFeeWei)
+                .and_then(|scaling| scaling.checked_add(mps))
+                .and_then(|scaling| mul_div_up(output.amount, scaling, mps))
+                .ok_or(InvalidOrderReason::Overflow)?;
+
+            outputs.push(ResolvedOutput {
+                token: output.token,
+                amount,
+                recipient: output.recipient,
+            });
+        }
+
+        Ok(OrderResolution::Resolved(ResolvedOrder {
+            input: ResolvedInput {
+                token: self.input.token,
+                amount: input_amount,
+            },
+            outputs,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{address, Address, Bytes, U256},
+        sol_types::SolValue,
+    };
+
+    use crate::types::{dutch_order::OrderInfo, InvalidOrderReason, OrderResolution};
+
+    use super::{PriorityCosignerData, PriorityInput, PriorityOrder, PriorityOutput};
+
+    fn priority_order(input_mps: u64, output_mps: u64) -> PriorityOrder {
+        PriorityOrder {
+            info: OrderInfo {
+                reactor: address!("000000001Ec5656dcdB24D90DFa42742738De729"),
+                swapper: address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"),
+                nonce: U256::from(1),
+                deadline: U256::from(1000),
+                additionalValidationContract: Address::ZERO,
+                additionalValidationData: Bytes::new(),
+            },
+            cosigner: Address::ZERO,
+            auctionStartBlock: U256::from(100),
+            baselinePriorityFeeWei: U256::from(10),
+            input: PriorityInput {
+                token: address!("4200000000000000000000000000000000000006"),
+                amount: U256::from(1_000_000),
+                mpsPerPriorityFeeWei: U256::from(input_mps),
+            },
+            outputs: vec![PriorityOutput {
+                token: address!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"),
+                amount: U256::from(1_000_000),
+                mpsPerPriorityFeeWei: U256::from(output_mps),
+                recipient: address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955"),
+            }],
+            cosignerData: PriorityCosignerData {
+                auctionTargetBlock: U256::ZERO,
+            },
+            cosignature: Bytes::new(),
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let encoded = Bytes::from(priority_order(0, 1).abi_encode());
+
+        let order = PriorityOrder::try_from_encoded(&encoded).unwrap();
+
+        assert_eq!(order.auctionStartBlock, U256::from(100));
+    }
+
+    #[test]
+    fn test_resolve_scales_outputs() {
+        let order = priority_order(0, 1000);
+
+        // 100 wei above the baseline: 1000 * 100 mps, i.e. +1%
+        match order.resolve(0, 100, U256::from(110)) {
+            OrderResolution::Resolved(resolved) => {
+                assert_eq!(resolved.input.amount, U256::from(1_000_000));
+                assert_eq!(resolved.outputs[0].amount, U256::from(1_010_000));
+            }
+            resolution => panic!("The order should be resolved, got {resolution:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_scales_input() {
+        let order = priority_order(1000, 0);
+
+        match order.resolve(0, 100, U256::from(110)) {
+            OrderResolution::Resolved(resolved) => {
+                assert_eq!(resolved.input.amount, U256::from(990_000));
+                assert_eq!(resolved.outputs[0].amount, U256::from(1_000_000));
+            }
+            resolution => panic!("The order should be resolved, got {resolution:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_with_input_and_outputs_scaled() {
+        let order = priority_order(1000, 1000);
+
+        assert!(matches!(
+            order.resolve(0, 100, U256::from(110)),
+            OrderResolution::Invalid(InvalidOrderReason::InputOutputScaled)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_before_auction_start() {
+        let order = priority_order(0, 1000);
+
+        assert!(matches!(
+            order.resolve(0, 99, U256::from(110)),
+            OrderResolution::NotFillable
+        ));
+    }
+
+    #[test]
+    fn test_resolve_with_cosigner_target_block() {
+        let mut order = priority_order(0, 1000);
+        order.cosigner = address!("4449Cd34d1eb1FEDCF02A1Be3834FfDe8E6A6180");
+        order.cosignerData.auctionTargetBlock = U256::from(90);
+
+        assert!(matches!(
+            order.resolve(0, 95, U256::from(110)),
+            OrderResolution::Resolved(_)
+        ));
+    }
+}
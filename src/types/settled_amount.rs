@@ -1,5 +1,17 @@
-use alloy::primitives::{Address, U256};
+use alloy::{
+    primitives::{Address, U256},
+    rpc::types::eth::Log,
+    sol,
+    sol_types::SolEvent,
+};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{FillEvent, Order, OrderOutput, ResolvedOrder};
+
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -9,3 +21,321 @@ pub struct SettledAmount {
     pub token_out: Address,
     pub amount_out: U256,
 }
+
+impl FillEvent {
+    /// Decodes the amounts the fill settled from the logs of its transaction, the same way the
+    /// API reports them: one entry per output, each carrying the input.
+    ///
+    /// The input is what Permit2 moved from the swapper to the filler, and each output what the
+    /// filler sent to its recipient. Native outputs are paid by the reactor without emitting any
+    /// log, but the reactor pays exactly the resolved amounts: they're taken from the order
+    /// resolved at the fill's block. Outputs sharing a token and a recipient are paid at once,
+    /// and the total is split between them in order, by their resolved amounts. Either way, the
+    /// fill can't be settled without the resolved order, see [`needs_resolution`]. When a
+    /// transaction fills several orders with the same tokens and recipients, their amounts
+    /// can't be told apart.
+    pub fn settle(
+        &mut self,
+        order: &Order,
+        logs: &[Log],
+        resolved: Option<&ResolvedOrder>,
+    ) -> Result<(), SettleError> {
+        let transfers = logs
+            .iter()
+            .filter_map(|log| {
+                let transfer = Transfer::decode_log_data(log.data(), true).ok()?;
+
+                Some((log.address(), transfer))
+            })
+            .collect::<Vec<_>>();
+
+        let transferred = |token: Address, from: Address, to: Address| {
+            transfers
+                .iter()
+                .filter(|(address, transfer)| {
+                    *address == token && transfer.from == from && transfer.to == to
+                })
+                .fold(U256::ZERO, |total, (_, transfer)| {
+                    total.saturating_add(transfer.value)
+                })
+        };
+
+        let amount_in = transferred(order.input.token, self.swapper, self.filler);
+        let amount_in = (!amount_in.is_zero()).then_some(amount_in);
+
+        let mut amounts_out = vec![U256::ZERO; order.outputs.len()];
+
+        for (i, output) in order.outputs.iter().enumerate() {
+            let pays = |other: &OrderOutput| {
+                other.token == output.token && other.recipient == output.recipient
+            };
+
+            // the outputs paid at once are settled with the first of them
+            if order.outputs[..i].iter().any(pays) {
+                continue;
+            }
+
+            let shared = order
+                .outputs
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| pays(other))
+                .map(|(j, _)| j)
+                .collect::<Vec<_>>();
+
+            let unresolved = || SettleError::UnresolvedOutput {
+                token: output.token,
+                recipient: output.recipient,
+            };
+
+            if let [only] = shared[..] {
+                if !output.token.is_zero() {
+                    amounts_out[only] = transferred(output.token, self.filler, output.recipient);
+
+                    continue;
+                }
+            }
+
+            let resolved = resolved.ok_or_else(unresolved)?;
+            let resolved_amounts = shared
+                .iter()
+                .map(|&j| resolved.outputs.get(j).map(|output| output.amount))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(unresolved)?;
+
+            let mut remaining = if output.token.is_zero() {
+                resolved_amounts
+                    .iter()
+                    .fold(U256::ZERO, |total, amount| total.saturating_add(*amount))
+            } else {
+                transferred(output.token, self.filler, output.recipient)
+            };
+
+            // any surplus goes to the last output
+            for (n, (&j, amount)) in shared.iter().zip(resolved_amounts).enumerate() {
+                let amount_out = if n + 1 == shared.len() {
+                    remaining
+                } else {
+                    amount.min(remaining)
+                };
+
+                amounts_out[j] = amount_out;
+                remaining -= amount_out;
+            }
+        }
+
+        let settled_amounts = order
+            .outputs
+            .iter()
+            .zip(amounts_out)
+            .map(|(output, amount_out)| SettledAmount {
+                token_in: amount_in.map(|_| order.input.token),
+                amount_in,
+                token_out: output.token,
+                amount_out,
+            })
+            .collect();
+
+        self.settled_amounts = Some(settled_amounts);
+
+        Ok(())
+    }
+}
+
+/// Whether [`FillEvent::settle`] needs the resolved order: when the order has native outputs, or
+/// outputs sharing a token and a recipient.
+pub fn needs_resolution(order: &Order) -> bool {
+    order.outputs.iter().enumerate().any(|(i, output)| {
+        output.token.is_zero()
+            || order.outputs[..i]
+                .iter()
+                .any(|other| other.token == output.token && other.recipient == output.recipient)
+    })
+}
+
+#[derive(Error, Debug)]
+pub enum SettleError {
+    #[error(
+        "The output of {token} paid to {recipient} can't be settled without the resolved order"
+    )]
+    UnresolvedOutput { token: Address, recipient: Address },
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{address, Address, Log as PrimitiveLog, U256},
+        rpc::types::eth::Log,
+        sol_types::SolEvent,
+    };
+
+    use crate::types::{
+        FillEvent, Order, OrderInput, OrderOutput, ResolvedInput, ResolvedOrder, ResolvedOutput,
+    };
+
+    use super::{needs_resolution, SettleError, Transfer};
+
+    const SWAPPER: Address = address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955");
+    const FILLER: Address = address!("b507d4ef5ed7a01e37cb578f497329cdb3c273a5");
+    const FEE_RECIPIENT: Address = address!("27213E28D7fDA5c57Fe9e5dD923818DBCcf71c47");
+    const POOL: Address = address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+    const USDC: Address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+
+    fn transfer(token: Address, from: Address, to: Address, value: u64) -> Log {
+        Log {
+            inner: PrimitiveLog {
+                address: token,
+                data: Transfer {
+                    from,
+                    to,
+                    value: U256::from(value),
+                }
+                .encode_log_data(),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn output(token: Address, recipient: Address) -> OrderOutput {
+        OrderOutput {
+            recipient,
+            start_amount: U256::ZERO,
+            end_amount: U256::ZERO,
+            token,
+        }
+    }
+
+    fn order() -> Order {
+        Order {
+            input: OrderInput {
+                token: USDC,
+                ..Default::default()
+            },
+            outputs: vec![
+                output(WETH, SWAPPER),
+                output(WETH, FEE_RECIPIENT),
+                output(Address::ZERO, SWAPPER),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn fill() -> FillEvent {
+        FillEvent {
+            filler: FILLER,
+            swapper: SWAPPER,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_settle() {
+        let logs = [
+            transfer(USDC, SWAPPER, FILLER, 1_000_000),
+            // the filler swaps the input in its callback
+            transfer(USDC, FILLER, POOL, 1_000_000),
+            transfer(WETH, POOL, FILLER, 510),
+            transfer(WETH, FILLER, SWAPPER, 500),
+            transfer(WETH, FILLER, FEE_RECIPIENT, 10),
+        ];
+
+        // the native output is paid by the reactor, without any log
+        let resolved = ResolvedOrder {
+            input: ResolvedInput {
+                token: USDC,
+                amount: U256::from(1_000_000),
+            },
+            outputs: vec![
+                ResolvedOutput {
+                    token: WETH,
+                    amount: U256::from(500),
+                    recipient: SWAPPER,
+                },
+                ResolvedOutput {
+                    token: WETH,
+                    amount: U256::from(10),
+                    recipient: FEE_RECIPIENT,
+                },
+                ResolvedOutput {
+                    token: Address::ZERO,
+                    amount: U256::from(42),
+                    recipient: SWAPPER,
+                },
+            ],
+        };
+
+        let mut fill = fill();
+        fill.settle(&order(), &logs, Some(&resolved)).unwrap();
+
+        let settled_amounts = fill.settled_amounts.unwrap();
+
+        assert_eq!(settled_amounts.len(), 3);
+        assert_eq!(settled_amounts[0].token_in, Some(USDC));
+        assert_eq!(settled_amounts[0].amount_in, Some(U256::from(1_000_000)));
+        assert_eq!(settled_amounts[0].token_out, WETH);
+        assert_eq!(settled_amounts[0].amount_out, U256::from(500));
+        assert_eq!(settled_amounts[1].amount_out, U256::from(10));
+        assert_eq!(settled_amounts[2].token_out, Address::ZERO);
+        assert_eq!(settled_amounts[2].amount_out, U256::from(42));
+    }
+
+    #[test]
+    fn test_settle_native_output_without_resolution() {
+        let mut fill = fill();
+
+        let err = fill.settle(&order(), &[], None).unwrap_err();
+
+        assert!(matches!(
+            err,
+            SettleError::UnresolvedOutput {
+                token: Address::ZERO,
+                recipient: SWAPPER
+            }
+        ));
+        assert!(fill.settled_amounts.is_none());
+    }
+
+    #[test]
+    fn test_settle_outputs_to_the_same_recipient() {
+        let order = Order {
+            outputs: vec![output(WETH, SWAPPER), output(WETH, SWAPPER)],
+            ..order()
+        };
+        assert!(needs_resolution(&order));
+
+        // both outputs are paid at once, with a surplus of 5
+        let logs = [
+            transfer(USDC, SWAPPER, FILLER, 1_000_000),
+            transfer(WETH, FILLER, SWAPPER, 515),
+        ];
+
+        let resolved_output = |amount: u64| ResolvedOutput {
+            token: WETH,
+            amount: U256::from(amount),
+            recipient: SWAPPER,
+        };
+        let resolved = ResolvedOrder {
+            input: ResolvedInput {
+                token: USDC,
+                amount: U256::from(1_000_000),
+            },
+            outputs: vec![resolved_output(500), resolved_output(10)],
+        };
+
+        let mut fill = fill();
+        fill.settle(&order, &logs, Some(&resolved)).unwrap();
+
+        let settled_amounts = fill.settled_amounts.unwrap();
+
+        assert_eq!(settled_amounts.len(), 2);
+        assert_eq!(settled_amounts[0].amount_out, U256::from(500));
+        assert_eq!(settled_amounts[1].amount_out, U256::from(15));
+
+        // without the resolved order, the total can't be split
+        assert!(matches!(
+            FillEvent::default().settle(&order, &logs, None),
+            Err(SettleError::UnresolvedOutput { .. })
+        ));
+    }
+}
@@ -0,0 +1,36 @@
+use std::{env, sync::Arc};
+
+use alloy::providers::ProviderBuilder;
+use dotenv::dotenv;
+use futures::TryStreamExt;
+use uniswapx::{BackfillConfig, ReactorClient};
+
+#[tokio::test]
+async fn test_backfill_fill_events() {
+    dotenv().ok();
+    let eth_rpc = env::var("ETH_RPC").unwrap();
+
+    let provider = ProviderBuilder::new().on_http(eth_rpc.parse().unwrap());
+    let reactor_client = ReactorClient::new(1).unwrap();
+
+    let events = reactor_client
+        .backfill_fill_events(
+            Arc::new(provider),
+            18270815,
+            18270840,
+            BackfillConfig {
+                chunk_size: 5,
+                concurrency: 2,
+                ..Default::default()
+            },
+        )
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+    assert!(events
+        .windows(2)
+        .all(|pair| pair[0].block_number <= pair[1].block_number));
+
+    println!("{events:#?}");
+}
@@ -0,0 +1,47 @@
+use alloy::primitives::address;
+use tower::Service;
+use uniswapx::{
+    types::{Cursor, OrdersRequest},
+    OrdersService,
+};
+
+/// Checks the cursors built from the last order of a page match the ones the API returns.
+#[tokio::test]
+async fn test_cursors_match_the_api() {
+    let mut orders_service = OrdersService::new(String::from("https://api.uniswap.org/v2/orders"));
+
+    let requests = [
+        OrdersRequest {
+            chain_id: Some(1),
+            ..Default::default()
+        },
+        OrdersRequest {
+            swapper: Some(address!("b8bff65b2eeb60d6b37312ca0740a742d5e7f955")),
+            ..Default::default()
+        },
+        OrdersRequest {
+            filler: Some(address!("ff8ba4d1fc3762f6154cc942ccf30049a2a0cec6")),
+            ..Default::default()
+        },
+    ];
+
+    for request in requests {
+        let request = OrdersRequest {
+            limit: Some(1),
+            ..request
+        };
+
+        let payload = orders_service.call(request.clone()).await.unwrap();
+
+        let api_cursor = payload
+            .cursor
+            .expect("The API should return a cursor")
+            .parse::<Cursor>()
+            .unwrap();
+        let cursor = request
+            .cursor_from_order(payload.orders.last().unwrap())
+            .unwrap();
+
+        assert_eq!(cursor, api_cursor);
+    }
+}
@@ -19,7 +19,7 @@ async fn test_get_fill_events_stream() {
 
     let front_end = connect.into_service().await.unwrap();
 
-    let reactor_client = ReactorClient::new(1);
+    let reactor_client = ReactorClient::new(1).unwrap();
 
     let mut stream = reactor_client
         .get_fill_events_stream(&front_end, Id::None)
@@ -1,10 +1,11 @@
-use std::pin::pin;
+use std::{pin::pin, time::Duration};
 
 use alloy::primitives::hex;
 use futures::StreamExt;
 use uniswapx::{
-    orders_stream,
+    open_orders_stream, orders_stream,
     types::{OrderStatus, OrderType, OrdersRequest},
+    OpenOrdersConfig,
 };
 
 #[tokio::test]
@@ -13,8 +14,7 @@ async fn test_order_stream() {
         chain_id: Some(1),
         ..Default::default()
     };
-    let mut orders_stream =
-        orders_stream(String::from("https://api.uniswap.org/v2/orders"), request);
+    let orders_stream = orders_stream(String::from("https://api.uniswap.org/v2/orders"), request);
 
     let mut orders_stream = pin!(orders_stream);
 
@@ -39,8 +39,7 @@ async fn test_open_order_stream() {
         order_status: Some(OrderStatus::Open),
         ..Default::default()
     };
-    let mut orders_stream =
-        orders_stream(String::from("https://api.uniswap.org/v2/orders"), request);
+    let orders_stream = orders_stream(String::from("https://api.uniswap.org/v2/orders"), request);
 
     let mut orders_stream = pin!(orders_stream);
 
@@ -48,3 +47,34 @@ async fn test_open_order_stream() {
         println!("{order:#?}");
     }
 }
+
+#[tokio::test]
+async fn test_open_orders_stream() {
+    let request = OrdersRequest {
+        chain_id: Some(42161),
+        order_type: Some(OrderType::DutchV1V2),
+        ..Default::default()
+    };
+    let config = OpenOrdersConfig {
+        removals: true,
+        ..Default::default()
+    };
+    let orders_stream = open_orders_stream(
+        String::from("https://api.uniswap.org/v2/orders"),
+        request,
+        config,
+    );
+
+    // the stream polls forever, only its first events are checked
+    let events = orders_stream
+        .take(10)
+        .take_until(tokio::time::sleep(Duration::from_secs(30)))
+        .collect::<Vec<_>>()
+        .await;
+
+    for event in &events {
+        println!("{event:#?}");
+    }
+
+    assert!(events.iter().all(Result::is_ok));
+}
@@ -1,16 +1,16 @@
-use alloy::primitives::hex;
+use alloy::primitives::{b256, hex};
 use tower::Service;
 use uniswapx::{types::OrdersRequest, OrdersService};
 
 #[tokio::test]
 async fn test_orders_service() {
     let mut orders_services = OrdersService::new(String::from("https://api.uniswap.org/v2/orders"));
-    let request = OrdersRequest {
-        order_hash: Some(String::from(
-            "0x33e043036e9323080855ee3011f720db6a315388dc6cfe5a9597b52188845d85",
-        )),
-        ..Default::default()
-    };
+    let request = OrdersRequest::builder()
+        .order_hash(b256!(
+            "33e043036e9323080855ee3011f720db6a315388dc6cfe5a9597b52188845d85"
+        ))
+        .build()
+        .unwrap();
 
     let payload = orders_services.call(request).await.unwrap();
 
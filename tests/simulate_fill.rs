@@ -0,0 +1,82 @@
+use std::{env, sync::Arc};
+
+use alloy::{primitives::address, providers::ProviderBuilder};
+use dotenv::dotenv;
+use uniswapx::{
+    types::{Order, OrderType},
+    FillSimulation, ReactorClient, ReactorClientError, ReactorConfig, ReactorDeployment,
+    ReactorError,
+};
+
+mod common;
+
+use common::{DUTCH_V1_ORDER, DUTCH_V1_SIGNATURE};
+
+// a mainnet V1 order, which expired in July 2023
+fn order() -> Order {
+    Order {
+        chain_id: 1,
+        order_type: OrderType::Dutch,
+        encoded_order: DUTCH_V1_ORDER.parse().unwrap(),
+        signature: DUTCH_V1_SIGNATURE.parse().unwrap(),
+        ..Default::default()
+    }
+}
+
+/// Runs against a mainnet fork, e.g. `anvil --fork-url $ETH_RPC`, listening on `ANVIL_RPC`.
+#[tokio::test]
+async fn test_simulate_expired_fill() {
+    dotenv().ok();
+    let anvil_rpc = env::var("ANVIL_RPC").unwrap();
+
+    let provider = ProviderBuilder::new().on_http(anvil_rpc.parse().unwrap());
+
+    // the order was settled by the first, since replaced, Dutch reactor
+    let reactor_client = ReactorClient::with_config(ReactorConfig {
+        reactors: vec![ReactorDeployment::new(
+            OrderType::Dutch,
+            address!("e80bf394d190851e215d5f67b67f8f5a52783f1e"),
+        )],
+        ..ReactorConfig::new(1).unwrap()
+    });
+
+    let simulation = reactor_client
+        .simulate_fill(
+            Arc::new(provider),
+            [order()],
+            address!("b507d4ef5ed7a01e37cb578f497329cdb3c273a5"),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        simulation,
+        FillSimulation::Reverted(ReactorError::DeadlinePassed)
+    ));
+}
+
+#[tokio::test]
+async fn test_simulate_fill_of_unknown_reactor() {
+    // the request is rejected before reaching the provider
+    let provider = ProviderBuilder::new().on_http("http://localhost:8545".parse().unwrap());
+    let reactor_client = ReactorClient::new(1).unwrap();
+
+    let err = reactor_client
+        .simulate_fill(
+            Arc::new(provider),
+            [order()],
+            address!("b507d4ef5ed7a01e37cb578f497329cdb3c273a5"),
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        ReactorClientError::UnknownReactor(reactor)
+            if reactor == address!("e80bf394d190851e215d5f67b67f8f5a52783f1e")
+    ));
+}
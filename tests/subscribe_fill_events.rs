@@ -0,0 +1,38 @@
+use std::env;
+
+use alloy::{
+    network::Ethereum, providers::RootProvider, pubsub::PubSubConnect, rpc::client::RpcClient,
+    transports::ws::WsConnect,
+};
+use dotenv::dotenv;
+use futures::StreamExt;
+use uniswapx::ReactorClient;
+
+#[tokio::test]
+async fn test_subscribe_fill_events() {
+    dotenv().ok();
+    let eth_rpc = env::var("ETH_WS_RPC").unwrap();
+
+    let connect = WsConnect {
+        url: eth_rpc,
+        auth: None,
+        config: None,
+    };
+
+    let front_end = connect.into_service().await.unwrap();
+    let provider = RootProvider::<_, Ethereum>::new(RpcClient::new(front_end, false));
+
+    let reactor_client = ReactorClient::new(1).unwrap();
+
+    let mut stream = reactor_client
+        .subscribe_fill_events(provider)
+        .await
+        .unwrap();
+
+    let v = stream.next().await;
+
+    println!("{v:?}");
+
+    // unsubscribes in the background
+    drop(stream);
+}